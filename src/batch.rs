@@ -0,0 +1,191 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use glam::DVec3;
+
+use crate::{
+    colliders::soa::{ColliderBatch, DVec3Lanes},
+    gjk::{GjkConfig, GjkResult, GJKNesterov},
+    simplex::Vertex,
+    support_map::SupportMap,
+};
+
+/// Distances of many collider pairs, in the order of `pairs`.
+///
+/// One solver is reused for all pairs, with the `rayon` feature the pairs are split over
+/// the thread pool and every worker reuses its own solver. The colliders have to be `Sync`
+/// with and without the feature, so enabling it does not break callers.
+pub fn distance_batch<A, B>(pairs: &[(A, B)], config: GjkConfig, max_iterations: usize) -> Vec<GjkResult>
+where
+    A: SupportMap + Sync,
+    B: SupportMap + Sync,
+{
+    map_with_solver(pairs, config, |gjk, (collider1, collider2)| {
+        gjk.distance_nesterov_accelerated(collider1, collider2, max_iterations)
+    })
+}
+
+/// Like `distance_batch` for pairs of indices into `colliders`.
+pub fn distance_batch_indexed<S>(
+    colliders: &[S],
+    pairs: &[(usize, usize)],
+    config: GjkConfig,
+    max_iterations: usize,
+) -> Vec<GjkResult>
+where
+    S: SupportMap + Sync,
+{
+    map_with_solver(pairs, config, |gjk, (index1, index2)| {
+        gjk.distance_nesterov_accelerated(&colliders[*index1], &colliders[*index2], max_iterations)
+    })
+}
+
+/// Distances of the pairs `(batch1[i], batch2[i])`, advancing all of them in lockstep.
+///
+/// Every iteration evaluates the support points of all lanes of both batches at once and steps
+/// the `GJKNesterov` of every pair that is not finished yet. The results equal
+/// `GJKNesterov::distance_nesterov_accelerated` of the single pairs with the same `config`.
+pub fn distance_lockstep<const N: usize>(
+    batch1: &ColliderBatch<N>,
+    batch2: &ColliderBatch<N>,
+    config: GjkConfig,
+    max_iterations: usize,
+) -> Vec<GjkResult> {
+    assert!(batch1.len() == batch2.len(), "both batches need the same number of colliders");
+    let len = batch1.len();
+
+    let mut lanes: Vec<GJKNesterov> = (0..len).map(|_| GJKNesterov::with_config(None, config)).collect();
+    let mut results: Vec<Option<GjkResult>> = vec![None; len];
+
+    let dirs = DVec3Lanes::from_fn(|i| lanes.get(i).map_or(DVec3::X, GJKNesterov::ray_guess));
+    let (support_points1, support_points2) = (batch1.core_support_points(&-dirs), batch2.core_support_points(&dirs));
+    let (margins1, margins2) = (batch1.margins(), batch2.margins());
+    for (i, gjk) in lanes.iter_mut().enumerate() {
+        gjk.start(margins1[i], margins2[i], Vertex::new(support_points1.lane(i), support_points2.lane(i)));
+    }
+
+    for iteration in 0..max_iterations {
+        for (gjk, result) in lanes.iter_mut().zip(results.iter_mut()) {
+            if result.is_none() {
+                *result = gjk.begin_iteration(iteration);
+            }
+        }
+        if results.iter().all(Option::is_some) {
+            break;
+        }
+
+        // Finished lanes keep searching along their last direction, their support points are ignored.
+        let dirs = DVec3Lanes::from_fn(|i| lanes.get(i).map_or(DVec3::X, GJKNesterov::search_direction));
+        let (support_points1, support_points2) = (batch1.core_support_points(&-dirs), batch2.core_support_points(&dirs));
+
+        for (i, (gjk, result)) in lanes.iter_mut().zip(results.iter_mut()).enumerate() {
+            if result.is_none() {
+                *result = gjk.step(iteration, Vertex::new(support_points1.lane(i), support_points2.lane(i)));
+            }
+        }
+    }
+
+    lanes.iter().zip(results)
+        .map(|(gjk, result)| result.unwrap_or_else(|| gjk.unfinished(max_iterations)))
+        .collect()
+}
+
+#[cfg(feature = "rayon")]
+fn map_with_solver<T, F>(items: &[T], config: GjkConfig, query: F) -> Vec<GjkResult>
+where
+    T: Sync,
+    F: Fn(&mut GJKNesterov, &T) -> GjkResult + Sync + Send,
+{
+    items.par_iter()
+        .map_init(|| GJKNesterov::with_config(None, config), |gjk, item| query(gjk, item))
+        .collect()
+}
+
+#[cfg(not(feature = "rayon"))]
+fn map_with_solver<T, F>(items: &[T], config: GjkConfig, query: F) -> Vec<GjkResult>
+where
+    T: Sync,
+    F: Fn(&mut GJKNesterov, &T) -> GjkResult,
+{
+    let mut gjk = GJKNesterov::with_config(None, config);
+    items.iter()
+        .map(|item| query(&mut gjk, item))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::{
+        colliders::{soa::ColliderBatch4, Collider},
+        gjk::{GjkConfig, GJKNesterov},
+    };
+
+    use super::{distance_batch, distance_batch_indexed, distance_lockstep};
+
+    #[test]
+    fn test_batch_matches_single_queries() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let colliders: Vec<Collider> = (0..50).map(|_| Collider::new_random_any(&mut rng, 0.5..3.0)).collect();
+        let indices: Vec<(usize, usize)> = (0..500).map(|_| (rng.gen_range(0..50), rng.gen_range(0..50))).collect();
+        let pairs: Vec<(Collider, Collider)> = indices.iter()
+            .map(|(i, j)| (colliders[*i].clone(), colliders[*j].clone()))
+            .collect();
+
+        let results = distance_batch(&pairs, GjkConfig::default(), 100);
+        let indexed_results = distance_batch_indexed(&colliders, &indices, GjkConfig::default(), 100);
+        assert!(results.len() == pairs.len());
+
+        for ((pair, result), indexed_result) in pairs.iter().zip(results.iter()).zip(indexed_results.iter()) {
+            let expected = GJKNesterov::new(None, 1e-6).distance_nesterov_accelerated(&pair.0, &pair.1, 100);
+            assert!(result.status == expected.status);
+            assert!(result.distance == expected.distance);
+            assert!(indexed_result.distance == expected.distance);
+        }
+    }
+
+    #[test]
+    fn test_lockstep_matches_single_queries() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let colliders: Vec<Collider> = (0..1000).map(|_| Collider::new_random_any(&mut rng, 0.5..3.0)).collect();
+        let config = GjkConfig { tolerance: 1e-8, ..Default::default() };
+
+        for collider1 in colliders.iter().take(50) {
+            for collider2 in colliders.iter().take(50) {
+                let colliders1: Vec<Collider> = colliders.iter().filter(|c| c.typ == collider1.typ).take(4).cloned().collect();
+                let colliders2: Vec<Collider> = colliders.iter().filter(|c| c.typ == collider2.typ).skip(4).take(rng.gen_range(1..=4)).cloned().collect();
+                let colliders1 = &colliders1[..colliders2.len()];
+
+                let results = distance_lockstep(&ColliderBatch4::new(colliders1), &ColliderBatch4::new(&colliders2), config, 100);
+                assert!(results.len() == colliders2.len());
+
+                for ((collider1, collider2), result) in colliders1.iter().zip(colliders2.iter()).zip(results.iter()) {
+                    let expected = GJKNesterov::with_config(None, config).distance_nesterov_accelerated(collider1, collider2, 100);
+                    assert!(result.status == expected.status);
+                    assert!((result.distance - expected.distance).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lockstep_follows_acceleration_options() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let colliders1: Vec<Collider> = (0..4).map(|_| Collider::new_random_box(&mut rng, 0.5..3.0, 0.5..3.0)).collect();
+        let colliders2: Vec<Collider> = (0..4).map(|_| Collider::new_random_sphere(&mut rng, 0.5..3.0, 0.5..3.0)).collect();
+        let (batch1, batch2) = (ColliderBatch4::new(&colliders1), ColliderBatch4::new(&colliders2));
+
+        for (use_nesterov_acceleration, normalize_support_direction) in [(false, false), (true, false), (true, true)] {
+            let config = GjkConfig { use_nesterov_acceleration, normalize_support_direction, ..Default::default() };
+            let results = distance_lockstep(&batch1, &batch2, config, 100);
+
+            for ((collider1, collider2), result) in colliders1.iter().zip(colliders2.iter()).zip(results.iter()) {
+                let expected = GJKNesterov::with_config(None, config).distance_nesterov_accelerated(collider1, collider2, 100);
+                assert!(result.status == expected.status);
+                assert!(result.iterations == expected.iterations);
+                assert!((result.distance - expected.distance).abs() < 1e-9);
+            }
+        }
+    }
+}
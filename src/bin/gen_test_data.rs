@@ -0,0 +1,206 @@
+use glam::{dvec3, DMat4, DQuat, DVec3};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use gjk::{
+    colliders::{Collider, ColliderType},
+    epa::EPA,
+    gjk::{ConvergenceCriterion, GjkConfig, GjkStatus, GJKNesterov, SubDistance},
+    json_loder::write_test_file,
+};
+
+/// Candidate witness points are computed to this absolute tolerance.
+const SOLVER_TOLERANCE: f64 = 1e-12;
+const SOLVER_ITERATIONS: usize = 10000;
+/// Any point inside both colliders certifies an overlap, so the penetration needs no high precision.
+const EPA_TOLERANCE: f64 = 1e-6;
+const EPA_ITERATIONS: usize = 100;
+/// Largest width of the interval the reference distance is certified to lie in.
+const REFERENCE_TOLERANCE: f64 = 1e-9;
+
+/// Usage: `gen_test_data [path] [count] [seed]`
+///
+/// The reference distances do not trust the solvers of the crate. GJK and EPA only propose witness points,
+/// whose distances to the colliders and support values are computed from the shape parameters in this file.
+/// Pairs whose distance cannot be certified are reported and fail the run, nothing is written then.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let path = args.next().unwrap_or_else(|| concat!(env!("CARGO_MANIFEST_DIR"), "/data/test_data.json").to_owned());
+    let count: usize = args.next().map_or(500, |arg| arg.parse().expect("count is not a number"));
+    let seed: u64 = args.next().map_or(0, |arg| arg.parse().expect("seed is not a number"));
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut test_data = Vec::with_capacity(count);
+    let mut uncertified = 0;
+
+    for i in 0..count {
+        let collider1 = random_collider(&mut rng);
+        let collider2 = random_collider(&mut rng);
+
+        match reference_distance(&collider1, &collider2) {
+            Ok(distance) => test_data.push((collider1, collider2, distance)),
+            Err(bounds) => {
+                uncertified += 1;
+                eprintln!("Case {i}: distance not certified, {bounds}");
+                eprintln!("  collider1: {}", serde_json::to_string(&collider1).unwrap());
+                eprintln!("  collider2: {}", serde_json::to_string(&collider2).unwrap());
+            },
+        }
+    }
+
+    if uncertified > 0 {
+        eprintln!("{uncertified} of {count} cases could not be certified, {path} was not written");
+        std::process::exit(1);
+    }
+
+    if let Err(error) = write_test_file(&path, &test_data) {
+        eprintln!("Could not write {path}: {error}");
+        std::process::exit(1);
+    }
+
+    let overlapping = test_data.iter().filter(|data| data.2 == 0.0).count();
+    println!("Wrote {count} cases to {path}, {overlapping} overlapping");
+}
+
+/// Random collider of every type, rotated and placed around the origin.
+fn random_collider(rng: &mut StdRng) -> Collider {
+    let collider = Collider::new_random_any(rng, 0.5..3.0);
+
+    let rotation = DQuat::from_xyzw(
+        rng.gen_range(-1.0..1.0),
+        rng.gen_range(-1.0..1.0),
+        rng.gen_range(-1.0..1.0),
+        rng.gen_range(-1.0..1.0)).normalize();
+    let center = dvec3(rng.gen_range(-3.0..3.0), rng.gen_range(-3.0..3.0), rng.gen_range(-3.0..3.0));
+
+    collider.with_transform(DMat4::from_rotation_translation(rotation, center))
+}
+
+/// Distance certified to `REFERENCE_TOLERANCE`, zero if the colliders overlap, or a description of the best bounds.
+///
+/// Separated colliders are certified by two points whose distance plus their distances to the colliders is an upper
+/// bound, and by a separating direction whose support values give a lower bound. Overlapping colliders are certified
+/// by a point that is closer to both colliders than the tolerance.
+fn reference_distance(collider1: &Collider, collider2: &Collider) -> Result<f64, String> {
+    let config = GjkConfig {
+        tolerance: SOLVER_TOLERANCE,
+        convergence_criterion: ConvergenceCriterion::Absolute,
+        sub_distance: SubDistance::SignedVolumes,
+        ..Default::default()
+    };
+
+    let mut gjk = GJKNesterov::with_config(None, config);
+    let result = gjk.distance_nesterov_accelerated(collider1, collider2, SOLVER_ITERATIONS);
+
+    let mut bounds = String::new();
+    if result.status != GjkStatus::Overlapping {
+        let points = gjk.closest_points();
+        let upper = points.point1.distance(points.point2) + outside(collider1, points.point1) + outside(collider2, points.point2);
+        let lower = (points.point2 - points.point1).try_normalize()
+            .map_or(0.0, |dir| (-max_along(collider2, -dir) - max_along(collider1, dir)).max(0.0));
+
+        if upper - lower <= REFERENCE_TOLERANCE {
+            return Ok(0.5 * (lower + upper));
+        }
+        bounds = format!("{lower} <= distance <= {upper}");
+    }
+
+    if result.inside() {
+        // The closest points of a simplex that encloses the origin coincide, EPA is tried if they do not.
+        let points = gjk.closest_points();
+        let penetration = EPA::new(EPA_TOLERANCE).penetration(&gjk, collider1, collider2, EPA_ITERATIONS);
+        let candidates = [(points.point1, points.point2)].into_iter()
+            .chain(penetration.iter().map(|penetration| (penetration.point1, penetration.point2)))
+            .flat_map(|(point1, point2)| [0.5 * (point1 + point2), point1, point2]);
+        let upper = candidates
+            .map(|point| outside(collider1, point) + outside(collider2, point))
+            .fold(f64::INFINITY, f64::min);
+
+        if upper <= REFERENCE_TOLERANCE {
+            return Ok(0.0);
+        }
+        if bounds.is_empty() {
+            bounds = format!("overlapping, 0 <= distance <= {upper}");
+        }
+    }
+
+    Err(bounds)
+}
+
+fn to_local(collider: &Collider, point: DVec3) -> DVec3 {
+    collider.transform.transpose() * (point - collider.center)
+}
+
+/// Largest projection of the collider onto the unit direction `dir`, from the shape parameters alone.
+fn max_along(collider: &Collider, dir: DVec3) -> f64 {
+    let l = collider.transform.transpose() * dir;
+    let radial = l.truncate().length();
+    let half_height = 0.5 * collider.height;
+    let half_size = 0.5 * collider.size;
+
+    let local = match collider.typ {
+        ColliderType::Sphere => collider.radius * l.length(),
+        ColliderType::Capluse => half_height * l.z.abs() + collider.radius * l.length(),
+        ColliderType::Cylinder => half_height * l.z.abs() + collider.radius * radial,
+        ColliderType::Box => half_size.dot(l.abs()),
+        ColliderType::Cone => (half_height * l.z).max(-half_height * l.z + collider.radius * radial),
+        ColliderType::Ellipsoid => (half_size * l).length(),
+        ColliderType::RoundedBox => (half_size - DVec3::splat(collider.radius)).dot(l.abs()) + collider.radius * l.length(),
+        ColliderType::ConvexHull => collider.hull.vertices.iter().map(|vertex| vertex.dot(l)).fold(f64::MIN, f64::max),
+    };
+
+    collider.center.dot(dir) + local
+}
+
+/// Upper bound of the distance of `point` to the collider, zero inside, from the shape parameters alone.
+/// Every bound is the distance to a point that is known to lie in the collider.
+fn outside(collider: &Collider, point: DVec3) -> f64 {
+    let p = to_local(collider, point);
+    let half_height = 0.5 * collider.height;
+    let half_size = 0.5 * collider.size;
+    let radial = p.truncate().length();
+
+    match collider.typ {
+        ColliderType::Sphere => (p.length() - collider.radius).max(0.0),
+        ColliderType::Capluse => {
+            let axis_point = dvec3(0.0, 0.0, p.z.clamp(-half_height, half_height));
+            (p.distance(axis_point) - collider.radius).max(0.0)
+        },
+        ColliderType::Cylinder => {
+            f64::hypot((radial - collider.radius).max(0.0), (p.z.abs() - half_height).max(0.0))
+        },
+        ColliderType::Box => (p.abs() - half_size).max(DVec3::ZERO).length(),
+        ColliderType::Cone => {
+            let z = p.z.clamp(-half_height, half_height);
+            let radius_at_z = collider.radius * (half_height - z) / collider.height;
+            f64::hypot((radial - radius_at_z).max(0.0), p.z - z)
+        },
+        ColliderType::Ellipsoid => {
+            // `p / scale` lies on the surface.
+            let scale = (p / half_size).length();
+            if scale <= 1.0 { 0.0 } else { p.length() * (1.0 - 1.0 / scale) }
+        },
+        ColliderType::RoundedBox => {
+            let inner = half_size - DVec3::splat(collider.radius);
+            (p.distance(p.clamp(-inner, inner)) - collider.radius).max(0.0)
+        },
+        ColliderType::ConvexHull => {
+            let vertices = &collider.hull.vertices;
+            if collider.hull.faces.is_empty() {
+                return f64::INFINITY;
+            }
+
+            // Pulls the point towards the centroid until it is behind every face plane.
+            let centroid = vertices.iter().sum::<DVec3>() / vertices.len() as f64;
+            let scale = collider.hull.faces.iter()
+                .map(|face| {
+                    let a = vertices[face[0]];
+                    let normal = (vertices[face[1]] - a).cross(vertices[face[2]] - a);
+                    let normal = if normal.dot(centroid - a) > 0.0 { -normal } else { normal };
+                    let (depth, reach) = (normal.dot(a - centroid), normal.dot(p - centroid));
+                    if reach > depth { depth / reach } else { 1.0 }
+                })
+                .fold(1.0_f64, f64::min);
+            (1.0 - scale) * p.distance(centroid)
+        },
+    }
+}
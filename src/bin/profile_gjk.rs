@@ -1,13 +1,54 @@
-use gjk::{json_loder::load_test_file, gjk::GJKNesterov};
+use std::time::Instant;
 
-fn main() {
+use gjk::{
+    colliders::Collider,
+    gjk::{ConvergenceCriterion, DistanceSolver, GjkConfig, GJKNesterov, SubDistance},
+    gjk_classic::{ClassicConfig, GJKClassic},
+    json_loder::load_test_file,
+};
 
-    let path = "../data/test_data.json";
-    let test_data = load_test_file(path);
+fn profile<S: DistanceSolver>(name: &str, solver: &mut S, test_data: &[(Collider, Collider, f64)]) {
+    let mut iteration_sum = 0;
+    let mut error_sum = 0.0;
 
-    for data in test_data {
-        let mut gjk = GJKNesterov::new(None, 1e-6);
-        gjk.distance_nesterov_accelerated(&data.0, &data.1, 100);
+    let start = Instant::now();
+    for data in test_data.iter() {
+        let result = solver.distance(&data.0, &data.1, 100);
+        iteration_sum += result.iterations;
+        error_sum += (result.distance.max(0.0) - data.2).abs();
     }
+    let elapsed = start.elapsed();
+
+    println!("{name}: {:?} iterations per case, {:?} mean error, {:?}",
+        (iteration_sum as f32) / test_data.len() as f32,
+        error_sum / test_data.len() as f64,
+        elapsed);
 }
 
+fn main() {
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/test_data.json");
+    let test_data = match load_test_file(path) {
+        Ok(test_data) => test_data,
+        Err(error) => {
+            eprintln!("Could not load {path}: {error}");
+            std::process::exit(1);
+        }
+    };
+
+    let signed_volumes = GjkConfig { sub_distance: SubDistance::SignedVolumes, ..Default::default() };
+    let variants = [
+        ("nesterov", GjkConfig::default()),
+        ("nesterov normalized", GjkConfig { normalize_support_direction: true, ..Default::default() }),
+        ("nesterov absolute", GjkConfig { convergence_criterion: ConvergenceCriterion::Absolute, ..Default::default() }),
+        ("nesterov signed volumes", signed_volumes),
+    ];
+
+    for (name, config) in variants {
+        profile(name, &mut GJKNesterov::with_config(None, config), &test_data);
+    }
+
+    let classic_signed_volumes = ClassicConfig { sub_distance: SubDistance::SignedVolumes, ..Default::default() };
+    profile("classic", &mut GJKClassic::with_config(None, ClassicConfig::default()), &test_data);
+    profile("classic signed volumes", &mut GJKClassic::with_config(None, classic_signed_volumes), &test_data);
+}
@@ -0,0 +1,77 @@
+use glam::{Mat3A, Mat4, Vec4Swizzles};
+
+use crate::scalar::Matrix3;
+
+use super::Collider;
+
+/// Single precision collider for engines that keep their poses in `f32`.
+///
+/// Its support function is evaluated in `f32`, query it with `GJKNesterov<f32>` and `GjkConfig::for_f32` as tolerances.
+pub type ColliderF32 = Collider<f32>;
+
+impl From<&Collider> for ColliderF32 {
+    fn from(collider: &Collider) -> Self {
+        Self {
+            typ: collider.typ,
+            transform: Mat3A::from_dmat3(collider.transform),
+            transform_transposed: Mat3A::from_dmat3(collider.transform_transposed),
+            center: collider.center.as_vec3a(),
+            radius: collider.radius as f32,
+            height: collider.height as f32,
+            size: collider.size.as_vec3a(),
+            hull: collider.hull.cast(),
+        }
+    }
+}
+
+impl ColliderF32 {
+    /// Places the collider at `collider2origin` without going through double precision.
+    pub fn set_transform(&mut self, collider2origin: Mat4) {
+        self.transform = Mat3A::from_mat4(collider2origin);
+        self.transform_transposed = self.transform.transpose();
+        self.center = collider2origin.w_axis.xyz().into();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::{Mat4, Quat, Vec3, Vec3A};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::{colliders::Collider, gjk::{GjkConfig, GJKNesterov}};
+
+    use super::ColliderF32;
+
+    #[test]
+    fn test_f32_matches_f64() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let mut gjk_f32 = GJKNesterov::<f32>::with_config(None, GjkConfig::for_f32());
+
+        for _ in 0..500 {
+            let collider1 = Collider::new_random_any(&mut rng, 0.5..3.0);
+            let collider2 = Collider::new_random_any(&mut rng, 0.5..3.0);
+
+            let expected = gjk.distance_nesterov_accelerated(&collider1, &collider2, 100).distance;
+            let distance = gjk_f32.distance_nesterov_accelerated(
+                &ColliderF32::from(&collider1), &ColliderF32::from(&collider2), 100).distance;
+
+            assert!((distance - expected).abs() < 1e-3 * expected.abs().max(1.0));
+        }
+    }
+
+    #[test]
+    fn test_set_transform() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let collider = Collider::new_random_any(&mut rng, 0.5..3.0);
+        let mut collider_f32 = ColliderF32::from(&collider);
+
+        let translation = Vec3::new(rng.gen_range(-5.0..5.0), rng.gen_range(-5.0..5.0), rng.gen_range(-5.0..5.0));
+        let pose = Mat4::from_rotation_translation(Quat::from_rotation_x(0.7), translation);
+        collider_f32.set_transform(pose);
+        let moved = collider.with_transform(pose.as_dmat4());
+
+        let dir = Vec3A::new(0.3, -0.5, 0.8);
+        assert!(collider_f32.get_support_point(dir).as_dvec3().distance(moved.get_support_point(dir.as_dvec3())) < 1e-4);
+    }
+}
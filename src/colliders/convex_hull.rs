@@ -0,0 +1,257 @@
+use glam::DVec3;
+
+use crate::{scalar::{Scalar, Vector3}, support_map::SupportMap};
+
+/// Convex hull of a point cloud in collider space.
+///
+/// Keeps only the points on the hull together with the triangles of the hull surface and the
+/// vertex adjacency of those triangles, which is used to hill-climb to the support point.
+/// The hull is built in `f64` and can be converted to `f32` with `cast`.
+#[derive(Clone, Debug, Default)]
+pub struct ConvexHull<F: Scalar = f64> {
+    pub vertices: Vec<F::Vec3>,
+    pub faces: Vec<[usize; 3]>,
+    pub adjacency: Vec<Vec<usize>>,
+}
+
+impl ConvexHull {
+    /// Builds the hull with an incremental algorithm.
+    /// Flat or degenerate point clouds keep all points and fall back to a linear scan as support function.
+    ///
+    /// Panics if `points` is empty or not finite, as the hull would have no support point.
+    pub fn new(points: &[DVec3]) -> Self {
+        assert!(!points.is_empty(), "a convex hull needs at least one point");
+        assert!(points.iter().all(|point| point.is_finite()), "the points of a convex hull need to be finite");
+
+        let extent = points.iter()
+            .fold(0.0_f64, |extent, point| extent.max(point.abs().max_element()));
+        let eps = 1e-9 * extent.max(1.0);
+
+        let Some(initial) = initial_tetrahedron(points, eps) else {
+            return Self {
+                vertices: points.to_vec(),
+                faces: Vec::new(),
+                adjacency: Vec::new(),
+            };
+        };
+
+        let mut faces: Vec<[usize; 3]> = Vec::new();
+        let [a, b, c, d] = initial;
+        for (i, j, k, opposite) in [(a, b, c, d), (a, d, b, c), (a, c, d, b), (b, d, c, a)] {
+            if face_normal(points, [i, j, k]).dot(points[opposite] - points[i]) > 0.0 {
+                faces.push([i, k, j]);
+            } else {
+                faces.push([i, j, k]);
+            }
+        }
+
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        for (p, point) in points.iter().enumerate() {
+            if initial.contains(&p) {
+                continue;
+            }
+
+            horizon.clear();
+            let mut i = 0;
+            while i < faces.len() {
+                let face = faces[i];
+                let normal = face_normal(points, face);
+                if normal.dot(*point - points[face[0]]) <= eps * normal.length() {
+                    i += 1;
+                    continue;
+                }
+
+                for e in 0..3 {
+                    let edge = (face[e], face[(e + 1) % 3]);
+                    match horizon.iter().position(|other| *other == (edge.1, edge.0)) {
+                        Some(position) => { horizon.swap_remove(position); },
+                        None => horizon.push(edge),
+                    }
+                }
+                faces.swap_remove(i);
+            }
+
+            faces.extend(horizon.iter().map(|(a, b)| [*a, *b, p]));
+        }
+
+        // Reindex so that only points on the hull remain.
+        let mut remap = vec![usize::MAX; points.len()];
+        let mut vertices = Vec::new();
+        for face in faces.iter_mut() {
+            for index in face.iter_mut() {
+                if remap[*index] == usize::MAX {
+                    remap[*index] = vertices.len();
+                    vertices.push(points[*index]);
+                }
+                *index = remap[*index];
+            }
+        }
+
+        let mut adjacency = vec![Vec::new(); vertices.len()];
+        for face in faces.iter() {
+            for e in 0..3 {
+                let (a, b) = (face[e], face[(e + 1) % 3]);
+                if !adjacency[a].contains(&b) {
+                    adjacency[a].push(b);
+                    adjacency[b].push(a);
+                }
+            }
+        }
+
+        Self {
+            vertices,
+            faces,
+            adjacency,
+        }
+    }
+
+    /// The hull with its vertices converted to another precision, the topology is shared.
+    pub fn cast<F: Scalar>(&self) -> ConvexHull<F> {
+        ConvexHull {
+            vertices: self.vertices.iter().map(|vertex| F::Vec3::from_dvec3(*vertex)).collect(),
+            faces: self.faces.clone(),
+            adjacency: self.adjacency.clone(),
+        }
+    }
+}
+
+impl<F: Scalar> ConvexHull<F> {
+    /// Index of the hull vertex furthest along `dir`.
+    pub fn support_index(&self, dir: F::Vec3) -> usize {
+        if self.adjacency.is_empty() {
+            return self.vertices.iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.dot(dir).total_cmp(&b.dot(dir)))
+                .map(|(i, _)| i)
+                .expect("convex hull without vertices");
+        }
+
+        // A vertex of a convex polytope without a better neighbor is a global maximum.
+        let mut best = 0;
+        let mut best_dot = self.vertices[0].dot(dir);
+        loop {
+            let mut improved = false;
+            for &neighbor in self.adjacency[best].iter() {
+                let neighbor_dot = self.vertices[neighbor].dot(dir);
+                if neighbor_dot > best_dot {
+                    best = neighbor;
+                    best_dot = neighbor_dot;
+                    improved = true;
+                }
+            }
+
+            if !improved {
+                return best;
+            }
+        }
+    }
+
+    pub fn bounding_radius(&self) -> F {
+        self.vertices.iter()
+            .fold(F::ZERO, |radius, vertex| radius.max(vertex.length()))
+    }
+}
+
+impl<F: Scalar> SupportMap<F> for ConvexHull<F> {
+    fn support_point(&self, dir: F::Vec3) -> F::Vec3 {
+        self.vertices[self.support_index(dir)]
+    }
+
+    fn core_support_vertex(&self, dir: F::Vec3) -> (F::Vec3, Option<usize>) {
+        let index = self.support_index(dir);
+        (self.vertices[index], Some(index))
+    }
+
+    fn core_vertex(&self, index: usize) -> Option<F::Vec3> {
+        self.vertices.get(index).copied()
+    }
+}
+
+fn face_normal(points: &[DVec3], face: [usize; 3]) -> DVec3 {
+    let a = points[face[0]];
+    (points[face[1]] - a).cross(points[face[2]] - a)
+}
+
+fn initial_tetrahedron(points: &[DVec3], eps: f64) -> Option<[usize; 4]> {
+    let a = 0;
+    let furthest = |score: &dyn Fn(DVec3) -> f64| {
+        points.iter()
+            .enumerate()
+            .map(|(i, point)| (i, score(*point)))
+            .max_by(|(_, x), (_, y)| x.total_cmp(y))
+    };
+
+    let p = *points.get(a)?;
+    let (b, distance) = furthest(&|point| point.distance(p))?;
+    if distance <= eps {
+        return None;
+    }
+
+    let ab = (points[b] - p).normalize();
+    let (c, distance) = furthest(&|point| (point - p).cross(ab).length())?;
+    if distance <= eps {
+        return None;
+    }
+
+    let normal = ab.cross(points[c] - p).normalize();
+    let (d, distance) = furthest(&|point| (point - p).dot(normal).abs())?;
+    if distance <= eps {
+        return None;
+    }
+
+    Some([a, b, c, d])
+}
+
+#[cfg(test)]
+mod test {
+    use glam::{dvec3, DVec3};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::ConvexHull;
+
+    #[test]
+    fn test_hull_of_cube_drops_interior_points() {
+        let mut points = Vec::new();
+        for i in 0..8 {
+            points.push(dvec3(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { -1.0 } else { 1.0 },
+            ));
+        }
+        points.push(dvec3(0.2, -0.3, 0.5));
+        points.push(dvec3(0.0, 1.0, 0.0));
+
+        let hull = ConvexHull::new(&points);
+        assert!(hull.vertices.len() == 8);
+        assert!(hull.faces.len() == 12);
+        assert!(hull.vertices[hull.support_index(dvec3(1.0, 2.0, -3.0))] == dvec3(1.0, 1.0, -1.0));
+    }
+
+    #[test]
+    fn test_hill_climbing_matches_linear_scan() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let points: Vec<DVec3> = (0..500)
+            .map(|_| dvec3(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)))
+            .collect();
+        let hull = ConvexHull::new(&points);
+
+        for _ in 0..1000 {
+            let dir = dvec3(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+            let best = points.iter().map(|point| point.dot(dir)).fold(f64::MIN, f64::max);
+            assert!((hull.vertices[hull.support_index(dir)].dot(dir) - best).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "a convex hull needs at least one point")]
+    fn test_empty_hull_is_rejected() {
+        ConvexHull::new(&[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the points of a convex hull need to be finite")]
+    fn test_non_finite_hull_is_rejected() {
+        ConvexHull::new(&[DVec3::ZERO, dvec3(f64::NAN, 0.0, 0.0)]);
+    }
+}
@@ -1,26 +1,46 @@
 use glam::{DVec3, DMat4, Vec4Swizzles, DMat3};
 
+pub mod collider_f32;
+pub mod convex_hull;
 pub mod random;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod soa;
 pub mod support_point;
 
+use convex_hull::ConvexHull;
+
+use crate::scalar::Scalar;
+
 #[derive(PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ColliderType {
     Sphere,
+    #[cfg_attr(feature = "serde", serde(rename = "Capsule"))]
     Capluse,
     Cylinder,
     Box,
+    ConvexHull,
+    Cone,
+    Ellipsoid,
+    RoundedBox,
 }
 
-pub struct Collider {
+/// A collider in double precision by default, `Collider<f32>` is the single precision variant, see `ColliderF32`.
+///
+/// With the `serde` feature it is (de)serialized in the format of the test files, see `json_loder`.
+#[derive(Clone)]
+pub struct Collider<F: Scalar = f64> {
     pub typ: ColliderType,
 
-    pub transform: DMat3,
-    pub transform_transposed: DMat3,
-    pub center: DVec3,
+    pub transform: F::Mat3,
+    pub transform_transposed: F::Mat3,
+    pub center: F::Vec3,
 
-    pub radius: f64,
-    pub height: f64,
-    pub size: DVec3,
+    pub radius: F,
+    pub height: F,
+    pub size: F::Vec3,
+    pub hull: ConvexHull<F>,
 }
 
 impl Collider {
@@ -33,9 +53,10 @@ impl Collider {
             transform, 
             transform_transposed: transform.transpose(),
             center: Self::get_center_from_collider2origin(&collider2origin), 
-            radius: radius, 
+            radius, 
             height: 0.0,
             size: DVec3::ZERO,
+            hull: ConvexHull::default(),
         }
     }
 
@@ -47,9 +68,10 @@ impl Collider {
             transform, 
             transform_transposed: transform.transpose(),
             center: Self::get_center_from_collider2origin(&collider2origin), 
-            radius: radius, 
-            height: height,
+            radius, 
+            height,
             size: DVec3::ZERO,
+            hull: ConvexHull::default(),
         }
     }
 
@@ -61,9 +83,10 @@ impl Collider {
             transform, 
             transform_transposed: transform.transpose(),
             center: Self::get_center_from_collider2origin(&collider2origin), 
-            radius: radius, 
-            height: height,
+            radius, 
+            height,
             size: DVec3::ZERO,
+            hull: ConvexHull::default(),
         }
     }
 
@@ -77,7 +100,85 @@ impl Collider {
             center: Self::get_center_from_collider2origin(&collider2origin), 
             radius: 0.0, 
             height: 0.0,
-            size: size,
+            size,
+            hull: ConvexHull::default(),
+        }
+    }
+
+    /// Cone along the z axis with its base at `-0.5 * height` and its apex at `0.5 * height`.
+    pub fn new_cone(collider2origin: DMat4, radius: f64, height: f64) -> Self {
+        let transform = DMat3::from_mat4(collider2origin);
+
+        Self { 
+            typ: ColliderType::Cone, 
+            transform, 
+            transform_transposed: transform.transpose(),
+            center: Self::get_center_from_collider2origin(&collider2origin), 
+            radius, 
+            height,
+            size: DVec3::ZERO,
+            hull: ConvexHull::default(),
+        }
+    }
+
+    /// Ellipsoid inscribed in a box of `size`.
+    pub fn new_ellipsoid(collider2origin: DMat4, size: DVec3) -> Self {
+        let transform = DMat3::from_mat4(collider2origin);
+
+        Self { 
+            typ: ColliderType::Ellipsoid, 
+            transform, 
+            transform_transposed: transform.transpose(),
+            center: Self::get_center_from_collider2origin(&collider2origin), 
+            radius: 0.0, 
+            height: 0.0,
+            size,
+            hull: ConvexHull::default(),
+        }
+    }
+
+    /// Box of `size` with edges and corners rounded by `radius`.
+    /// The radius is clamped to half of the smallest side.
+    pub fn new_rounded_box(collider2origin: DMat4, size: DVec3, radius: f64) -> Self {
+        let transform = DMat3::from_mat4(collider2origin);
+
+        Self { 
+            typ: ColliderType::RoundedBox, 
+            transform, 
+            transform_transposed: transform.transpose(),
+            center: Self::get_center_from_collider2origin(&collider2origin), 
+            radius: radius.min(0.5 * size.min_element()), 
+            height: 0.0,
+            size,
+            hull: ConvexHull::default(),
+        }
+    }
+
+    /// Panics if `vertices` is empty or not finite, see `ConvexHull::new`.
+    pub fn new_convex_hull(collider2origin: DMat4, vertices: &[DVec3]) -> Self {
+        let transform = DMat3::from_mat4(collider2origin);
+
+        Self { 
+            typ: ColliderType::ConvexHull, 
+            transform, 
+            transform_transposed: transform.transpose(),
+            center: Self::get_center_from_collider2origin(&collider2origin), 
+            radius: 0.0, 
+            height: 0.0,
+            size: DVec3::ZERO,
+            hull: ConvexHull::new(vertices),
+        }
+    }
+
+    /// A copy of the collider placed at `collider2origin`.
+    pub fn with_transform(&self, collider2origin: DMat4) -> Self {
+        let transform = DMat3::from_mat4(collider2origin);
+
+        Self {
+            transform,
+            transform_transposed: transform.transpose(),
+            center: Self::get_center_from_collider2origin(&collider2origin),
+            ..self.clone()
         }
     }
 
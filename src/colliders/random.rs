@@ -15,6 +15,20 @@ impl Collider {
         }
     }
 
+    /// Like `new_random`, but picks any `ColliderType`. Draws a different random stream than `new_random`.
+    pub fn new_random_any<R: SampleRange<f64> + Clone>(rng: &mut impl Rng, size_range: R) -> Self {
+        match rng.gen_range(0..=7) {
+            0 => Self::new_random_sphere(rng, size_range.to_owned(), size_range.to_owned()),
+            1 => Self::new_random_capsule(rng, size_range.to_owned(), size_range.to_owned(), size_range.to_owned()),
+            2 => Self::new_random_cylinder(rng, size_range.to_owned(), size_range.to_owned(), size_range.to_owned()),
+            3 => Self::new_random_box(rng, size_range.to_owned(), size_range.to_owned()),
+            4 => Self::new_random_cone(rng, size_range.to_owned(), size_range.to_owned(), size_range.to_owned()),
+            5 => Self::new_random_ellipsoid(rng, size_range.to_owned(), size_range.to_owned()),
+            6 => Self::new_random_rounded_box(rng, size_range.to_owned(), size_range.to_owned(), size_range.to_owned()),
+            _ => Self::new_random_convex_hull(rng, size_range.to_owned(), size_range.to_owned()),
+        }
+    }
+
     pub fn new_random_sphere<R: SampleRange<f64> + Clone>(
         rng: &mut impl Rng, 
         center_rang: R,
@@ -48,6 +62,67 @@ impl Collider {
             rng.gen_range(radius_range), 
             rng.gen_range(height_range))
     }
+
+    pub fn new_random_box<R: SampleRange<f64> + Clone>(
+        rng: &mut impl Rng, 
+        center_rang: R,
+        size_range: R) -> Self
+    {
+        Self::new_box(
+            random_mat4(rng, center_rang), 
+            random_vec3(rng, size_range))
+    }
+
+    pub fn new_random_cone<R: SampleRange<f64> + Clone>(
+        rng: &mut impl Rng, 
+        center_rang: R,
+        radius_range: R,
+        height_range: R) -> Self
+    {
+        Self::new_cone(
+            random_mat4(rng, center_rang), 
+            rng.gen_range(radius_range), 
+            rng.gen_range(height_range))
+    }
+
+    pub fn new_random_ellipsoid<R: SampleRange<f64> + Clone>(
+        rng: &mut impl Rng, 
+        center_rang: R,
+        size_range: R) -> Self
+    {
+        Self::new_ellipsoid(
+            random_mat4(rng, center_rang), 
+            random_vec3(rng, size_range))
+    }
+
+    pub fn new_random_rounded_box<R: SampleRange<f64> + Clone>(
+        rng: &mut impl Rng, 
+        center_rang: R,
+        size_range: R,
+        radius_range: R) -> Self
+    {
+        Self::new_rounded_box(
+            random_mat4(rng, center_rang), 
+            random_vec3(rng, size_range),
+            rng.gen_range(radius_range))
+    }
+
+    /// Hull of 4 to 16 points around the center, each at half a random size from it.
+    pub fn new_random_convex_hull<R: SampleRange<f64> + Clone>(
+        rng: &mut impl Rng, 
+        center_rang: R,
+        size_range: R) -> Self
+    {
+        let collider2origin = random_mat4(rng, center_rang);
+        let vertices: Vec<DVec3> = (0..rng.gen_range(4..=16))
+            .map(|_| {
+                let dir = random_vec3(rng, -1.0..1.0).try_normalize().unwrap_or(DVec3::X);
+                dir * 0.5 * rng.gen_range(size_range.to_owned())
+            })
+            .collect();
+
+        Self::new_convex_hull(collider2origin, &vertices)
+    }
 }
 
 fn random_vec3<R: SampleRange<f64> + Clone>(rng: &mut impl Rng, range: R) -> DVec3 {
@@ -59,4 +134,32 @@ fn random_vec3<R: SampleRange<f64> + Clone>(rng: &mut impl Rng, range: R) -> DVe
 
 fn random_mat4<R: SampleRange<f64> + Clone>(rng: &mut impl Rng, range: R) -> DMat4 {
     DMat4::from_translation(random_vec3(rng, range))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::colliders::{Collider, ColliderType};
+
+    #[test]
+    fn test_new_random_keeps_the_primitives() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..200 {
+            let collider = Collider::new_random(&mut rng, 0.5..3.0);
+            assert!(matches!(collider.typ, ColliderType::Sphere | ColliderType::Capluse | ColliderType::Cylinder));
+        }
+    }
+
+    #[test]
+    fn test_new_random_any_covers_every_type() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let colliders: Vec<Collider> = (0..200).map(|_| Collider::new_random_any(&mut rng, 0.5..3.0)).collect();
+
+        for typ in [
+            ColliderType::Sphere, ColliderType::Capluse, ColliderType::Cylinder, ColliderType::Box,
+            ColliderType::ConvexHull, ColliderType::Cone, ColliderType::Ellipsoid, ColliderType::RoundedBox,
+        ] {
+            assert!(colliders.iter().any(|collider| collider.typ == typ));
+        }
+    }
+}
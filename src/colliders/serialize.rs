@@ -0,0 +1,334 @@
+use glam::{DMat4, DQuat, DVec3};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::json_loder::{LoadErrorKind, PoseFields};
+
+use super::{Collider, ColliderType};
+
+type Rows = [[f64; 4]; 4];
+
+/// Collider as written in the test files, with only the parameters its `type` uses.
+/// It is written with `collider2origin` as row major matrix and read with any pose encoding of `PoseFields`.
+#[derive(Serialize, Deserialize)]
+struct ColliderFields {
+    #[serde(rename = "type")]
+    typ: ColliderType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    collider2origin: Option<Rows>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    center: Option<DVec3>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rotation: Option<DQuat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    euler: Option<DVec3>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    radius: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    height: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<DVec3>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vertices: Option<Vec<DVec3>>,
+}
+
+/// Compact layout for binary formats, which cannot skip fields.
+#[derive(Serialize, Deserialize)]
+enum ColliderRepr {
+    Sphere { collider2origin: Rows, radius: f64 },
+    Capsule { collider2origin: Rows, radius: f64, height: f64 },
+    Cylinder { collider2origin: Rows, radius: f64, height: f64 },
+    Box { collider2origin: Rows, size: DVec3 },
+    Cone { collider2origin: Rows, radius: f64, height: f64 },
+    Ellipsoid { collider2origin: Rows, size: DVec3 },
+    RoundedBox { collider2origin: Rows, size: DVec3, radius: f64 },
+    ConvexHull { collider2origin: Rows, vertices: Vec<DVec3> },
+}
+
+impl Serialize for Collider {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            ColliderFields::from(self).serialize(serializer)
+        } else {
+            ColliderRepr::from(self).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Collider {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = if deserializer.is_human_readable() {
+            let fields = ColliderFields::deserialize(deserializer)?;
+            ColliderRepr::try_from(fields).map_err(D::Error::custom)?
+        } else {
+            ColliderRepr::deserialize(deserializer)?
+        };
+
+        repr.validate().map_err(D::Error::custom)?;
+        Ok(Collider::from(repr))
+    }
+}
+
+fn collider2origin_rows(collider: &Collider) -> Rows {
+    let mut collider2origin = DMat4::from_mat3(collider.transform);
+    collider2origin.w_axis = collider.center.extend(1.0);
+    collider2origin.transpose().to_cols_array_2d()
+}
+
+impl From<&Collider> for ColliderFields {
+    fn from(collider: &Collider) -> Self {
+        let (radius, height, size) = match collider.typ {
+            ColliderType::Sphere => (Some(collider.radius), None, None),
+            ColliderType::Capluse | ColliderType::Cylinder | ColliderType::Cone => (Some(collider.radius), Some(collider.height), None),
+            ColliderType::Box | ColliderType::Ellipsoid => (None, None, Some(collider.size)),
+            ColliderType::RoundedBox => (Some(collider.radius), None, Some(collider.size)),
+            ColliderType::ConvexHull => (None, None, None),
+        };
+
+        Self {
+            typ: collider.typ,
+            collider2origin: Some(collider2origin_rows(collider)),
+            center: None,
+            rotation: None,
+            euler: None,
+            radius,
+            height,
+            size,
+            vertices: (collider.typ == ColliderType::ConvexHull).then(|| collider.hull.vertices.clone()),
+        }
+    }
+}
+
+impl From<&Collider> for ColliderRepr {
+    fn from(collider: &Collider) -> Self {
+        let collider2origin = collider2origin_rows(collider);
+        let (radius, height, size) = (collider.radius, collider.height, collider.size);
+
+        match collider.typ {
+            ColliderType::Sphere => Self::Sphere { collider2origin, radius },
+            ColliderType::Capluse => Self::Capsule { collider2origin, radius, height },
+            ColliderType::Cylinder => Self::Cylinder { collider2origin, radius, height },
+            ColliderType::Box => Self::Box { collider2origin, size },
+            ColliderType::Cone => Self::Cone { collider2origin, radius, height },
+            ColliderType::Ellipsoid => Self::Ellipsoid { collider2origin, size },
+            ColliderType::RoundedBox => Self::RoundedBox { collider2origin, size, radius },
+            ColliderType::ConvexHull => Self::ConvexHull { collider2origin, vertices: collider.hull.vertices.clone() },
+        }
+    }
+}
+
+impl TryFrom<ColliderFields> for ColliderRepr {
+    type Error = String;
+
+    fn try_from(fields: ColliderFields) -> Result<Self, Self::Error> {
+        let pose = PoseFields {
+            collider2origin: fields.collider2origin.map(|rows| DMat4::from_cols_array_2d(&rows).transpose()),
+            center: fields.center,
+            rotation: fields.rotation,
+            euler: fields.euler,
+        };
+        let collider2origin = pose.pose()
+            .map_err(|(key, kind)| format!("{key}: {kind}"))?
+            .transpose().to_cols_array_2d();
+
+        let missing = |key: &str| format!("{key}: missing field");
+        let radius = || fields.radius.ok_or_else(|| missing("radius"));
+        let height = || fields.height.ok_or_else(|| missing("height"));
+        let size = || fields.size.ok_or_else(|| missing("size"));
+
+        Ok(match fields.typ {
+            ColliderType::Sphere => Self::Sphere { collider2origin, radius: radius()? },
+            ColliderType::Capluse => Self::Capsule { collider2origin, radius: radius()?, height: height()? },
+            ColliderType::Cylinder => Self::Cylinder { collider2origin, radius: radius()?, height: height()? },
+            ColliderType::Box => Self::Box { collider2origin, size: size()? },
+            ColliderType::Cone => Self::Cone { collider2origin, radius: radius()?, height: height()? },
+            ColliderType::Ellipsoid => Self::Ellipsoid { collider2origin, size: size()? },
+            ColliderType::RoundedBox => Self::RoundedBox { collider2origin, size: size()?, radius: radius()? },
+            ColliderType::ConvexHull => Self::ConvexHull { collider2origin, vertices: fields.vertices.ok_or_else(|| missing("vertices"))? },
+        })
+    }
+}
+
+impl ColliderRepr {
+    /// The checks of the test file loader, which both layouts have to pass before the collider is built.
+    fn validate(&self) -> Result<(), String> {
+        let error = |key: &str, kind: LoadErrorKind| format!("{key}: {kind}");
+
+        let (collider2origin, dimensions, size, vertices) = match self {
+            Self::Sphere { collider2origin, radius } => (collider2origin, vec![("radius", *radius)], None, None),
+            Self::Capsule { collider2origin, radius, height }
+            | Self::Cylinder { collider2origin, radius, height }
+            | Self::Cone { collider2origin, radius, height } => (collider2origin, vec![("radius", *radius), ("height", *height)], None, None),
+            Self::Box { collider2origin, size } | Self::Ellipsoid { collider2origin, size } => (collider2origin, Vec::new(), Some(*size), None),
+            Self::RoundedBox { collider2origin, size, radius } => (collider2origin, vec![("radius", *radius)], Some(*size), None),
+            Self::ConvexHull { collider2origin, vertices } => (collider2origin, Vec::new(), None, Some(vertices)),
+        };
+
+        let pose = PoseFields { collider2origin: Some(DMat4::from_cols_array_2d(collider2origin).transpose()), ..Default::default() };
+        pose.pose().map_err(|(key, kind)| error(key, kind))?;
+
+        let sizes = size.iter().flat_map(|size| size.to_array().into_iter().enumerate().map(|(i, value)| (format!("size[{i}]"), value)));
+        for (key, value) in dimensions.into_iter().map(|(key, value)| (key.to_owned(), value)).chain(sizes) {
+            if !value.is_finite() {
+                return Err(error(&key, LoadErrorKind::NonFinite));
+            }
+            if value < 0.0 {
+                return Err(error(&key, LoadErrorKind::Negative));
+            }
+        }
+
+        if let Some(vertices) = vertices {
+            if vertices.is_empty() {
+                return Err(error("vertices", LoadErrorKind::Empty));
+            }
+            if let Some(i) = vertices.iter().position(|vertex| !vertex.is_finite()) {
+                return Err(error(&format!("vertices[{i}]"), LoadErrorKind::NonFinite));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<ColliderRepr> for Collider {
+    fn from(repr: ColliderRepr) -> Self {
+        let from_rows = |rows: Rows| DMat4::from_cols_array_2d(&rows).transpose();
+
+        match repr {
+            ColliderRepr::Sphere { collider2origin, radius } => Collider::new_sphere(from_rows(collider2origin), radius),
+            ColliderRepr::Capsule { collider2origin, radius, height } => Collider::new_capluse(from_rows(collider2origin), radius, height),
+            ColliderRepr::Cylinder { collider2origin, radius, height } => Collider::new_cylinder(from_rows(collider2origin), radius, height),
+            ColliderRepr::Box { collider2origin, size } => Collider::new_box(from_rows(collider2origin), size),
+            ColliderRepr::Cone { collider2origin, radius, height } => Collider::new_cone(from_rows(collider2origin), radius, height),
+            ColliderRepr::Ellipsoid { collider2origin, size } => Collider::new_ellipsoid(from_rows(collider2origin), size),
+            ColliderRepr::RoundedBox { collider2origin, size, radius } => Collider::new_rounded_box(from_rows(collider2origin), size, radius),
+            ColliderRepr::ConvexHull { collider2origin, vertices } => Collider::new_convex_hull(from_rows(collider2origin), &vertices),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::dvec3;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::{colliders::{Collider, ColliderType}, json_loder::{load_test_file, parse_collider, write_test_file, TestCase}};
+
+    use super::ColliderRepr;
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..200 {
+            let collider = Collider::new_random_any(&mut rng, 0.5..3.0);
+            let json = serde_json::to_string(&collider).unwrap();
+            let loaded: Collider = serde_json::from_str(&json).unwrap();
+
+            assert!(loaded.typ == collider.typ);
+            // The same parser as the test files reads the serialized collider.
+            let parsed = parse_collider(&serde_json::from_str(&json).unwrap()).unwrap();
+
+            for _ in 0..10 {
+                let dir = dvec3(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+                assert!(loaded.get_support_point(dir) == collider.get_support_point(dir));
+                assert!(parsed.get_support_point(dir) == collider.get_support_point(dir));
+            }
+        }
+    }
+
+    #[test]
+    fn test_test_case() {
+        let json = r#"{
+            "collider1": {
+                "type": "Capsule",
+                "collider2origin": [[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 2.0], [0.0, 0.0, 1.0, 3.0], [0.0, 0.0, 0.0, 1.0]],
+                "radius": 0.5,
+                "height": 2.0
+            },
+            "collider2": {
+                "type": "ConvexHull",
+                "collider2origin": [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]],
+                "vertices": [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+            },
+            "distance": 1.5
+        }"#;
+
+        let test_case: TestCase = serde_json::from_str(json).unwrap();
+        assert!(test_case.collider1.typ == ColliderType::Capluse);
+        assert!(test_case.collider1.center == dvec3(1.0, 2.0, 3.0));
+        assert!(test_case.collider2.hull.vertices.len() == 4);
+        assert!(test_case.distance == 1.5);
+
+        assert!(serde_json::to_string(&ColliderType::Capluse).unwrap() == r#""Capsule""#);
+
+        let collider: Collider = serde_json::from_str(r#"{ "type": "Sphere", "center": [1.0, 2.0, 3.0], "euler": [0.1, 0.2, 0.3], "radius": 1.0 }"#).unwrap();
+        assert!(collider.center == dvec3(1.0, 2.0, 3.0));
+        let error = serde_json::from_str::<Collider>(r#"{ "type": "Sphere", "center": [1.0, 2.0, 3.0], "rotation": [0.0, 0.0, 0.0, 3.0], "radius": 1.0 }"#).err().unwrap();
+        assert!(error.to_string().starts_with("rotation: not a proper rotation"));
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let test_cases: Vec<TestCase> = (0..100)
+            .map(|_| TestCase {
+                collider1: Collider::new_random_any(&mut rng, 0.5..3.0),
+                collider2: Collider::new_random_any(&mut rng, 0.5..3.0),
+                distance: rng.gen_range(0.0..10.0),
+            })
+            .collect();
+
+        let bytes = bincode::serialize(&test_cases).unwrap();
+        let loaded: Vec<TestCase> = bincode::deserialize(&bytes).unwrap();
+
+        let dir = dvec3(0.3, -0.5, 0.8);
+        for (test_case, expected) in loaded.iter().zip(test_cases.iter()) {
+            assert!(test_case.distance == expected.distance);
+            assert!(test_case.collider1.get_support_point(dir) == expected.collider1.get_support_point(dir));
+            assert!(test_case.collider2.get_support_point(dir) == expected.collider2.get_support_point(dir));
+        }
+    }
+
+    #[test]
+    fn test_binary_validation() {
+        let identity = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+        let mut projective = identity;
+        projective[3][1] = 0.5;
+
+        let cases = [
+            (ColliderRepr::Sphere { collider2origin: projective, radius: 1.0 }, "collider2origin: bottom row is not [0, 0, 0, 1]"),
+            (ColliderRepr::Capsule { collider2origin: identity, radius: -1.0, height: 1.0 }, "radius: negative value"),
+            (ColliderRepr::Box { collider2origin: identity, size: dvec3(1.0, f64::NAN, 1.0) }, "size[1]: non-finite value"),
+            (ColliderRepr::ConvexHull { collider2origin: identity, vertices: Vec::new() }, "vertices: empty array"),
+        ];
+        for (repr, message) in cases {
+            let bytes = bincode::serialize(&repr).unwrap();
+            let error = bincode::deserialize::<Collider>(&bytes).err().unwrap();
+            assert!(error.to_string() == message);
+        }
+
+        let error = serde_json::from_str::<Collider>(r#"{ "type": "ConvexHull", "center": [0.0, 0.0, 0.0], "vertices": [] }"#).err().unwrap();
+        assert!(error.to_string().starts_with("vertices: empty array"));
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let test_data: Vec<(Collider, Collider, f64)> = (0..50)
+            .map(|_| (Collider::new_random_any(&mut rng, 0.5..3.0), Collider::new_random_any(&mut rng, 0.5..3.0), rng.gen_range(0.0..10.0)))
+            .collect();
+
+        let path = std::env::temp_dir().join(format!("gjk_write_round_trip_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        write_test_file(path, &test_data).unwrap();
+        let loaded = load_test_file(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert!(loaded.len() == test_data.len());
+        for (loaded, expected) in loaded.iter().zip(test_data.iter()) {
+            let dir = dvec3(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+            assert!(loaded.0.get_support_point(dir) == expected.0.get_support_point(dir));
+            assert!(loaded.1.get_support_point(dir) == expected.1.get_support_point(dir));
+            assert!(loaded.2 == expected.2);
+        }
+    }
+}
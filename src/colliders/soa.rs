@@ -0,0 +1,311 @@
+use std::ops::Neg;
+
+use glam::DVec3;
+
+use super::{convex_hull::ConvexHull, Collider, ColliderType};
+
+/// `N` vectors in structure of arrays layout, every component is a contiguous array
+/// so that loops over the lanes are plain loops over `[f64; N]` the compiler can vectorize.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DVec3Lanes<const N: usize> {
+    pub x: [f64; N],
+    pub y: [f64; N],
+    pub z: [f64; N],
+}
+
+pub type DVec3x4 = DVec3Lanes<4>;
+pub type DVec3x8 = DVec3Lanes<8>;
+
+impl<const N: usize> DVec3Lanes<N> {
+    pub const ZERO: Self = Self { x: [0.0; N], y: [0.0; N], z: [0.0; N] };
+
+    pub fn splat(v: DVec3) -> Self {
+        Self { x: [v.x; N], y: [v.y; N], z: [v.z; N] }
+    }
+
+    pub fn from_fn(f: impl FnMut(usize) -> DVec3) -> Self {
+        let lanes: [DVec3; N] = std::array::from_fn(f);
+        Self { x: lanes.map(|v| v.x), y: lanes.map(|v| v.y), z: lanes.map(|v| v.z) }
+    }
+
+    pub fn lane(&self, i: usize) -> DVec3 {
+        DVec3::new(self.x[i], self.y[i], self.z[i])
+    }
+
+    pub fn set_lane(&mut self, i: usize, v: DVec3) {
+        self.x[i] = v.x;
+        self.y[i] = v.y;
+        self.z[i] = v.z;
+    }
+
+    pub fn dot(&self, other: &Self) -> [f64; N] {
+        std::array::from_fn(|i| self.x[i] * other.x[i] + self.y[i] * other.y[i] + self.z[i] * other.z[i])
+    }
+}
+
+impl<const N: usize> Neg for DVec3Lanes<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self { x: self.x.map(|x| -x), y: self.y.map(|y| -y), z: self.z.map(|z| -z) }
+    }
+}
+
+/// Up to `N` colliders of the same `ColliderType` in structure of arrays layout.
+///
+/// The type is matched once per call and the support function of that shape runs as loops over the
+/// `[f64; N]` components of all lanes, as do the rotations into and out of the collider spaces.
+/// Only convex hulls search their vertices lane by lane.
+/// Missing lanes repeat the last collider, their results are meaningless.
+#[derive(Clone)]
+pub struct ColliderBatch<const N: usize> {
+    typ: ColliderType,
+    len: usize,
+
+    /// Columns of the rotation of every lane.
+    axes: [DVec3Lanes<N>; 3],
+    center: DVec3Lanes<N>,
+
+    radius: [f64; N],
+    height: [f64; N],
+    size: DVec3Lanes<N>,
+    /// Hulls of the colliders without the padding, empty unless the batch holds convex hulls.
+    hulls: Vec<ConvexHull>,
+}
+
+pub type ColliderBatch4 = ColliderBatch<4>;
+pub type ColliderBatch8 = ColliderBatch<8>;
+
+impl<const N: usize> ColliderBatch<N> {
+    /// Panics if `colliders` is empty, longer than `N` or of different types.
+    pub fn new(colliders: &[Collider]) -> Self {
+        assert!(!colliders.is_empty() && colliders.len() <= N, "a batch holds 1 to {} colliders", N);
+        let typ = colliders[0].typ;
+        assert!(colliders.iter().all(|collider| collider.typ == typ), "all colliders of a batch need the same type");
+
+        let lane = |i: usize| &colliders[i.min(colliders.len() - 1)];
+        let hulls = match typ {
+            ColliderType::ConvexHull => colliders.iter().map(|collider| collider.hull.clone()).collect(),
+            _ => Vec::new(),
+        };
+
+        Self {
+            typ,
+            len: colliders.len(),
+            axes: [
+                DVec3Lanes::from_fn(|i| lane(i).transform.x_axis),
+                DVec3Lanes::from_fn(|i| lane(i).transform.y_axis),
+                DVec3Lanes::from_fn(|i| lane(i).transform.z_axis),
+            ],
+            center: DVec3Lanes::from_fn(|i| lane(i).center),
+            radius: std::array::from_fn(|i| lane(i).radius),
+            height: std::array::from_fn(|i| lane(i).height),
+            size: DVec3Lanes::from_fn(|i| lane(i).size),
+            hulls,
+        }
+    }
+
+    pub fn typ(&self) -> ColliderType {
+        self.typ
+    }
+
+    /// Number of colliders in the batch without the padding.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn margins(&self) -> [f64; N] {
+        match self.typ {
+            ColliderType::Sphere | ColliderType::Capluse | ColliderType::RoundedBox => self.radius,
+            _ => [0.0; N],
+        }
+    }
+
+    /// `SupportMap::core_support_point` of every lane along its direction in `dirs`.
+    pub fn core_support_points(&self, dirs: &DVec3Lanes<N>) -> DVec3Lanes<N> {
+        let [axis_x, axis_y, axis_z] = &self.axes;
+        let local_dirs = DVec3Lanes { x: axis_x.dot(dirs), y: axis_y.dot(dirs), z: axis_z.dot(dirs) };
+
+        let local = match self.typ {
+            ColliderType::Sphere => DVec3Lanes::ZERO,
+            ColliderType::Capluse => self.capsule_core(&local_dirs),
+            ColliderType::Cylinder => self.cylinder(&local_dirs),
+            ColliderType::Box => self.box_corners(&local_dirs, &[0.0; N]),
+            ColliderType::ConvexHull => self.hull_vertices(&local_dirs),
+            ColliderType::Cone => self.cone(&local_dirs),
+            ColliderType::Ellipsoid => self.ellipsoid(&local_dirs),
+            ColliderType::RoundedBox => self.box_corners(&local_dirs, &self.radius),
+        };
+        self.to_world(&local)
+    }
+
+    /// `SupportMap::support_point` of every lane along its direction in `dirs`.
+    pub fn support_points(&self, dirs: &DVec3Lanes<N>) -> DVec3Lanes<N> {
+        let mut points = self.core_support_points(dirs);
+        let margins = self.margins();
+        let lengths = dirs.dot(dirs).map(f64::sqrt);
+
+        for i in 0..N {
+            let scale = if lengths[i] > 0.0 { margins[i] / lengths[i] } else { 0.0 };
+            points.x[i] += dirs.x[i] * scale;
+            points.y[i] += dirs.y[i] * scale;
+            points.z[i] += dirs.z[i] * scale;
+        }
+        points
+    }
+
+    /// Corners of the boxes shrunk by `shrink` on every side along `local_dirs`.
+    fn box_corners(&self, local_dirs: &DVec3Lanes<N>, shrink: &[f64; N]) -> DVec3Lanes<N> {
+        let corner = |dirs: &[f64; N], size: &[f64; N]| -> [f64; N] {
+            std::array::from_fn(|i| {
+                let half = 0.5 * size[i] - shrink[i];
+                if dirs[i] < 0.0 { -half } else { half }
+            })
+        };
+
+        DVec3Lanes {
+            x: corner(&local_dirs.x, &self.size.x),
+            y: corner(&local_dirs.y, &self.size.y),
+            z: corner(&local_dirs.z, &self.size.z),
+        }
+    }
+
+    fn capsule_core(&self, local_dirs: &DVec3Lanes<N>) -> DVec3Lanes<N> {
+        let mut points = DVec3Lanes::ZERO;
+        for i in 0..N {
+            let half_height = 0.5 * self.height[i];
+            points.z[i] = if local_dirs.z[i] > 0.0 { half_height } else { -half_height };
+        }
+        points
+    }
+
+    fn cylinder(&self, local_dirs: &DVec3Lanes<N>) -> DVec3Lanes<N> {
+        let mut points = DVec3Lanes::ZERO;
+        for i in 0..N {
+            let half_height = 0.5 * self.height[i];
+            let s = (local_dirs.x[i] * local_dirs.x[i] + local_dirs.y[i] * local_dirs.y[i]).sqrt();
+
+            points.z[i] = if local_dirs.z[i] < 0.0 { -half_height } else { half_height };
+            if s == 0.0 {
+                points.x[i] = self.radius[i];
+            } else {
+                let d = self.radius[i] / s;
+                points.x[i] = local_dirs.x[i] * d;
+                points.y[i] = local_dirs.y[i] * d;
+            }
+        }
+        points
+    }
+
+    fn cone(&self, local_dirs: &DVec3Lanes<N>) -> DVec3Lanes<N> {
+        let lengths = local_dirs.dot(local_dirs).map(f64::sqrt);
+
+        let mut points = DVec3Lanes::ZERO;
+        for (i, length) in lengths.iter().enumerate() {
+            let (radius, height) = (self.radius[i], self.height[i]);
+            let sin_angle = radius / (radius * radius + height * height).sqrt();
+            let s = (local_dirs.x[i] * local_dirs.x[i] + local_dirs.y[i] * local_dirs.y[i]).sqrt();
+
+            if local_dirs.z[i] > length * sin_angle {
+                points.z[i] = 0.5 * height;
+            } else {
+                points.z[i] = -0.5 * height;
+                if s != 0.0 {
+                    let d = radius / s;
+                    points.x[i] = local_dirs.x[i] * d;
+                    points.y[i] = local_dirs.y[i] * d;
+                }
+            }
+        }
+        points
+    }
+
+    fn ellipsoid(&self, local_dirs: &DVec3Lanes<N>) -> DVec3Lanes<N> {
+        let mut points = DVec3Lanes::ZERO;
+        for i in 0..N {
+            let (radius_x, radius_y, radius_z) = (0.5 * self.size.x[i], 0.5 * self.size.y[i], 0.5 * self.size.z[i]);
+            let (x, y, z) = (radius_x * local_dirs.x[i], radius_y * local_dirs.y[i], radius_z * local_dirs.z[i]);
+            let s = (x * x + y * y + z * z).sqrt();
+
+            if s == 0.0 {
+                points.x[i] = radius_x;
+            } else {
+                points.x[i] = radius_x * x / s;
+                points.y[i] = radius_y * y / s;
+                points.z[i] = radius_z * z / s;
+            }
+        }
+        points
+    }
+
+    /// The vertex search of a hull does not map to array loops, it runs lane by lane.
+    fn hull_vertices(&self, local_dirs: &DVec3Lanes<N>) -> DVec3Lanes<N> {
+        DVec3Lanes::from_fn(|i| {
+            let hull = &self.hulls[i.min(self.len - 1)];
+            hull.vertices[hull.support_index(local_dirs.lane(i))]
+        })
+    }
+
+    fn to_world(&self, local: &DVec3Lanes<N>) -> DVec3Lanes<N> {
+        let [axis_x, axis_y, axis_z] = &self.axes;
+        let component = |center: &[f64; N], x: &[f64; N], y: &[f64; N], z: &[f64; N]| -> [f64; N] {
+            std::array::from_fn(|i| center[i] + (x[i] * local.x[i] + y[i] * local.y[i] + z[i] * local.z[i]))
+        };
+
+        DVec3Lanes {
+            x: component(&self.center.x, &axis_x.x, &axis_y.x, &axis_z.x),
+            y: component(&self.center.y, &axis_x.y, &axis_y.y, &axis_z.y),
+            z: component(&self.center.z, &axis_x.z, &axis_y.z, &axis_z.z),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::{dvec3, DVec3};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::{colliders::{convex_hull::ConvexHull, Collider, ColliderType}, support_map::SupportMap};
+
+    use super::{ColliderBatch8, DVec3x8};
+
+    #[test]
+    fn test_batch_support_points_match_scalar() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut colliders: Vec<Collider> = Vec::new();
+        while colliders.len() < 2000 {
+            colliders.push(Collider::new_random_any(&mut rng, 0.5..3.0));
+        }
+        for _ in 0..7 {
+            let box_collider = Collider::new_random_box(&mut rng, 0.5..3.0, 0.5..3.0);
+            let vertices: Vec<DVec3> = (0..20)
+                .map(|_| dvec3(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)))
+                .collect();
+            colliders.push(Collider { hull: ConvexHull::new(&vertices), typ: ColliderType::ConvexHull, ..box_collider });
+        }
+
+        for typ in [
+            ColliderType::Sphere, ColliderType::Capluse, ColliderType::Cylinder, ColliderType::Box,
+            ColliderType::ConvexHull, ColliderType::Cone, ColliderType::Ellipsoid, ColliderType::RoundedBox,
+        ] {
+            let of_type: Vec<Collider> = colliders.iter().filter(|collider| collider.typ == typ).take(7).cloned().collect();
+            assert!(!of_type.is_empty());
+            let batch = ColliderBatch8::new(&of_type);
+
+            for _ in 0..50 {
+                let dirs = DVec3x8::from_fn(|_| dvec3(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)));
+                let core = batch.core_support_points(&dirs);
+                let full = batch.support_points(&dirs);
+
+                for (i, collider) in of_type.iter().enumerate() {
+                    assert!(core.lane(i).distance(collider.core_support_point(dirs.lane(i))) < 1e-12);
+                    assert!(full.lane(i).distance(collider.support_point(dirs.lane(i))) < 1e-9);
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,27 @@
-use glam::{DVec3, dvec3};
+use crate::{scalar::{Scalar, Vector3}, support_map::SupportMap};
 
-use super::{Collider, ColliderType};
+use super::{convex_hull::ConvexHull, Collider, ColliderType};
 
+/// Shape of a collider in its local space. The support functions of all shapes are implemented once here,
+/// for both precisions of `Collider`. `ColliderBatch` has its own versions over the lanes.
+#[derive(Clone, Copy)]
+pub(crate) struct LocalShape<'a, F: Scalar> {
+    pub(crate) typ: ColliderType,
+    pub(crate) radius: F,
+    pub(crate) height: F,
+    pub(crate) size: F::Vec3,
+    /// Only read for `ColliderType::ConvexHull`.
+    pub(crate) hull: &'a ConvexHull<F>,
+}
+
+impl<F: Scalar> LocalShape<'_, F> {
+    /// Furthest point of the shape along `local_dir`.
+    pub(crate) fn support_point(&self, local_dir: F::Vec3) -> F::Vec3 {
+        let half_height = F::HALF * self.height;
 
-impl Collider {
-    pub fn get_support_point(&self, dir: DVec3) -> DVec3 {
         match self.typ {
             ColliderType::Sphere => {
-                dir.normalize() * self.radius + self.center
+                local_dir.normalize() * self.radius
             },
     
             ColliderType::Capluse => {
@@ -19,23 +33,16 @@ impl Collider {
                 * https://github.com/bulletphysics/bullet3/blob/e306b274f1885f32b7e9d65062aa942b398805c2/src/BulletCollision/CollisionShapes/btConvexShape.cpp#L228
                 (Copyright (c) 2003-2009 Erwin Coumans, zlib license)
                 */
-                let local_dir = self.transform_transposed * dir;
-
-                let s = (local_dir.x * local_dir.x + local_dir.y * local_dir.y + local_dir.z * local_dir.z).sqrt();
+                let s = local_dir.length();
     
-                let mut local_vertex = if s == 0.0 { 
-                    dvec3(self.radius, 0.0, 0.0) 
+                let local_vertex = if s == F::ZERO { 
+                    F::Vec3::new(self.radius, F::ZERO, F::ZERO) 
                 } else {  
                     local_dir * (self.radius / s)
                 };
 
-                local_vertex.z += if local_vertex.z > 0.0 { 
-                    0.5 * self.height 
-                } else { 
-                    -0.5 * self.height 
-                };
-
-                self.center + (self.transform * local_vertex)
+                let z = if local_vertex.z() > F::ZERO { half_height } else { -half_height };
+                local_vertex + F::Vec3::new(F::ZERO, F::ZERO, z)
             },
     
             ColliderType::Cylinder => {
@@ -47,20 +54,16 @@ impl Collider {
                 * https://github.com/bulletphysics/bullet3/blob/e306b274f1885f32b7e9d65062aa942b398805c2/src/BulletCollision/CollisionShapes/btConvexShape.cpp#L167
                 (Copyright (c) 2003-2009 Erwin Coumans, zlib license) 
                 */
-                let local_dir =  self.transform_transposed * dir;
-
-                let s = (local_dir.x * local_dir.x + local_dir.y * local_dir.y).sqrt();
+                let s = (local_dir.x() * local_dir.x() + local_dir.y() * local_dir.y()).sqrt();
 
-                let z = if local_dir.z < 0.0 { -0.5 * self.height } else { 0.5 * self.height };
+                let z = if local_dir.z() < F::ZERO { -half_height } else { half_height };
                 
-                let local_vertex = if s == 0.0 { 
-                    dvec3(self.radius, 0.0, z) 
+                if s == F::ZERO { 
+                    F::Vec3::new(self.radius, F::ZERO, z) 
                 } else {  
                     let d = self.radius / s;
-                    dvec3(local_dir.x * d, local_dir.y * d, z) 
-                };
-
-                self.center + (self.transform * local_vertex)
+                    F::Vec3::new(local_dir.x() * d, local_dir.y() * d, z) 
+                }
             },    
             ColliderType::Box => {
                 /*
@@ -71,22 +74,218 @@ impl Collider {
                 * https://github.com/bulletphysics/bullet3/blob/e306b274f1885f32b7e9d65062aa942b398805c2/src/BulletCollision/CollisionShapes/btConvexShape.cpp#L167
                 (Copyright (c) 2003-2009 Erwin Coumans, zlib license) 
                 */
-                
-                let local_dir = self.transform_transposed * dir;
+                box_vertex::<F>(self.size * F::HALF, local_dir)
+            },    
+            ColliderType::Cone => {
+                /*
+                You can find a similar implementation here:
+    
+                * https://github.com/bulletphysics/bullet3/blob/e306b274f1885f32b7e9d65062aa942b398805c2/src/BulletCollision/CollisionShapes/btConeShape.cpp#L62
+                (Copyright (c) 2003-2009 Erwin Coumans, zlib license) 
+                */
+                let sin_angle = self.radius / (self.radius * self.radius + self.height * self.height).sqrt();
 
-                let mut local_vertex = self.size * 0.5;
-                if local_dir.x < 0.0 {
-                    local_vertex.x *= -1.0;
-                }
-                if local_dir.y < 0.0 {
-                    local_vertex.y *= -1.0;
+                if local_dir.z() > local_dir.length() * sin_angle {
+                    F::Vec3::new(F::ZERO, F::ZERO, half_height)
+                } else {
+                    let s = (local_dir.x() * local_dir.x() + local_dir.y() * local_dir.y()).sqrt();
+                    if s == F::ZERO {
+                        F::Vec3::new(F::ZERO, F::ZERO, -half_height)
+                    } else {
+                        let d = self.radius / s;
+                        F::Vec3::new(local_dir.x() * d, local_dir.y() * d, -half_height)
+                    }
                 }
-                if local_dir.z < 0.0 {
-                    local_vertex.z *= -1.0;
+            },
+            ColliderType::Ellipsoid => {
+                let radii = self.size * F::HALF;
+                let scaled_dir = radii * local_dir;
+                let s = scaled_dir.length();
+
+                if s == F::ZERO {
+                    F::Vec3::new(radii.x(), F::ZERO, F::ZERO)
+                } else {
+                    radii * scaled_dir / s
                 }
+            },
+            ColliderType::RoundedBox => {
+                self.core_support_point(local_dir) + local_dir.normalize_or_zero() * self.radius
+            },
+            ColliderType::ConvexHull => {
+                self.hull.support_point(local_dir)
+            },
+        }
+    }
 
-                self.center + (self.transform * local_vertex)
-            },    
+    /// Radius of the sphere swept around the core shape.
+    pub(crate) fn margin(&self) -> F {
+        match self.typ {
+            ColliderType::Sphere | ColliderType::Capluse | ColliderType::RoundedBox => self.radius,
+            _ => F::ZERO,
+        }
+    }
+
+    /// Furthest point of the shape without its margin along `local_dir`.
+    pub(crate) fn core_support_point(&self, local_dir: F::Vec3) -> F::Vec3 {
+        match self.typ {
+            ColliderType::Sphere => F::Vec3::ZERO,
+            ColliderType::Capluse => {
+                let z = if local_dir.z() > F::ZERO { F::HALF * self.height } else { -F::HALF * self.height };
+
+                F::Vec3::new(F::ZERO, F::ZERO, z)
+            },
+            ColliderType::RoundedBox => {
+                box_vertex::<F>(self.size * F::HALF - F::Vec3::splat(self.radius), local_dir)
+            },
+            _ => self.support_point(local_dir),
+        }
+    }
+
+    /// Radius of a sphere around the origin of the local space that encloses the shape.
+    pub(crate) fn bounding_radius(&self) -> F {
+        let half_height = F::HALF * self.height;
+
+        match self.typ {
+            ColliderType::Sphere => self.radius,
+            ColliderType::Capluse => self.radius + half_height,
+            ColliderType::Cylinder => F::Vec3::new(self.radius, F::ZERO, half_height).length(),
+            ColliderType::Box => F::HALF * self.size.length(),
+            ColliderType::ConvexHull => self.hull.bounding_radius(),
+            ColliderType::Cone => F::Vec3::new(self.radius, F::ZERO, half_height).length(),
+            ColliderType::Ellipsoid => F::HALF * self.size.max_element(),
+            ColliderType::RoundedBox => F::HALF * self.size.length(),
+        }
+    }
+}
+
+fn box_vertex<F: Scalar>(half_size: F::Vec3, local_dir: F::Vec3) -> F::Vec3 {
+    let sign = |dir: F, half: F| if dir < F::ZERO { -half } else { half };
+    F::Vec3::new(
+        sign(local_dir.x(), half_size.x()),
+        sign(local_dir.y(), half_size.y()),
+        sign(local_dir.z(), half_size.z()))
+}
+
+impl<F: Scalar> Collider<F> {
+    pub(crate) fn local_shape(&self) -> LocalShape<'_, F> {
+        LocalShape {
+            typ: self.typ,
+            radius: self.radius,
+            height: self.height,
+            size: self.size,
+            hull: &self.hull,
+        }
+    }
+
+    pub fn get_support_point(&self, dir: F::Vec3) -> F::Vec3 {
+        // A sphere ignores the rotation and scale of its transform.
+        if self.typ == ColliderType::Sphere {
+            return dir.normalize() * self.radius + self.center;
+        }
+
+        let local_dir = self.transform_transposed * dir;
+
+        self.center + (self.transform * self.local_shape().support_point(local_dir))
+    }
+}
+
+impl<F: Scalar> SupportMap<F> for Collider<F> {
+    fn support_point(&self, dir: F::Vec3) -> F::Vec3 {
+        self.get_support_point(dir)
+    }
+
+    fn margin(&self) -> F {
+        self.local_shape().margin()
+    }
+
+    fn core_support_point(&self, dir: F::Vec3) -> F::Vec3 {
+        let local_dir = self.transform_transposed * dir;
+
+        self.center + (self.transform * self.local_shape().core_support_point(local_dir))
+    }
+
+    #[inline]
+    fn core_support_vertex(&self, dir: F::Vec3) -> (F::Vec3, Option<usize>) {
+        if self.typ != ColliderType::ConvexHull {
+            return (self.core_support_point(dir), None);
+        }
+
+        let index = self.hull.support_index(self.transform_transposed * dir);
+        (self.center + (self.transform * self.hull.vertices[index]), Some(index))
+    }
+
+    fn core_vertex(&self, index: usize) -> Option<F::Vec3> {
+        if self.typ != ColliderType::ConvexHull {
+            return None;
+        }
+
+        self.hull.vertices.get(index).map(|vertex| self.center + (self.transform * *vertex))
+    }
+
+    fn center(&self) -> Option<F::Vec3> {
+        Some(self.center)
+    }
+
+    fn bounding_radius(&self) -> Option<F> {
+        Some(self.local_shape().bounding_radius())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::{dvec3, DMat4, DQuat, DVec3, EulerRot};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use crate::colliders::Collider;
+
+    #[test]
+    fn test_new_shape_support_points() {
+        let cone = Collider::new_cone(DMat4::IDENTITY, 1.0, 2.0);
+        assert!(cone.get_support_point(dvec3(0.0, 0.0, 1.0)) == dvec3(0.0, 0.0, 1.0));
+        assert!(cone.get_support_point(dvec3(1.0, 0.0, 0.0)) == dvec3(1.0, 0.0, -1.0));
+
+        let ellipsoid = Collider::new_ellipsoid(DMat4::IDENTITY, dvec3(2.0, 4.0, 6.0));
+        assert!(ellipsoid.get_support_point(dvec3(0.0, 1.0, 0.0)) == dvec3(0.0, 2.0, 0.0));
+        assert!(ellipsoid.get_support_point(dvec3(0.0, 0.0, -1.0)) == dvec3(0.0, 0.0, -3.0));
+
+        let rounded_box = Collider::new_rounded_box(DMat4::IDENTITY, dvec3(2.0, 2.0, 2.0), 0.5);
+        assert!(rounded_box.get_support_point(dvec3(1.0, 0.0, 0.0)) == dvec3(1.0, 0.5, 0.5));
+        let corner = rounded_box.get_support_point(dvec3(1.0, 1.0, 1.0));
+        assert!((corner - (DVec3::splat(0.5) + DVec3::splat(0.5 / 3.0_f64.sqrt()))).length() < 1e-12);
+    }
+
+    #[test]
+    fn test_sphere_support_point_in_world_space() {
+        let sphere = Collider::new_sphere(DMat4::from_scale_rotation_translation(
+            dvec3(2.0, 3.0, 4.0),
+            DQuat::from_rotation_z(0.5),
+            dvec3(1.0, 0.0, 0.0)), 1.0);
+        assert!(sphere.get_support_point(dvec3(0.0, 2.0, 0.0)) == dvec3(1.0, 1.0, 0.0));
+        assert!(sphere.get_support_point(dvec3(-1.0, 0.0, 0.0)) == dvec3(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_support_points_are_maximal() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut random_dir = || dvec3(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+
+        let transform = DMat4::from_rotation_translation(
+            DQuat::from_euler(EulerRot::XYZ, 0.3, -1.2, 0.7),
+            dvec3(1.0, -2.0, 0.5));
+        let colliders = [
+            Collider::new_cone(transform, 1.0, 2.0),
+            Collider::new_ellipsoid(transform, dvec3(1.0, 2.0, 3.0)),
+            Collider::new_rounded_box(transform, dvec3(1.0, 2.0, 3.0), 0.3),
+        ];
+
+        for collider in colliders.iter() {
+            for _ in 0..200 {
+                let dir = random_dir();
+                let best = collider.get_support_point(dir).dot(dir);
+                for _ in 0..20 {
+                    assert!(collider.get_support_point(random_dir()).dot(dir) <= best + 1e-12);
+                }
+            }
         }
     }
-}
\ No newline at end of file
+}
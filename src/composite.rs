@@ -0,0 +1,206 @@
+use glam::{DMat4, DVec3};
+
+use crate::{colliders::Collider, support_map::SupportMap};
+
+/// A shape swept by a sphere of radius `margin`.
+#[derive(Clone, Debug)]
+pub struct Inflated<S> {
+    pub shape: S,
+    pub margin: f64,
+}
+
+impl<S: SupportMap> Inflated<S> {
+    pub fn new(shape: S, margin: f64) -> Self {
+        Self { shape, margin }
+    }
+}
+
+impl<S: SupportMap> SupportMap for Inflated<S> {
+    fn support_point(&self, dir: DVec3) -> DVec3 {
+        self.shape.support_point(dir) + dir.normalize_or_zero() * self.margin
+    }
+
+    fn margin(&self) -> f64 {
+        self.shape.margin() + self.margin
+    }
+
+    fn core_support_point(&self, dir: DVec3) -> DVec3 {
+        self.shape.core_support_point(dir)
+    }
+
+    fn core_support_vertex(&self, dir: DVec3) -> (DVec3, Option<usize>) {
+        self.shape.core_support_vertex(dir)
+    }
+
+    fn core_vertex(&self, index: usize) -> Option<DVec3> {
+        self.shape.core_vertex(index)
+    }
+
+    fn center(&self) -> Option<DVec3> {
+        self.shape.center()
+    }
+
+    fn bounding_radius(&self) -> Option<f64> {
+        self.shape.bounding_radius().map(|radius| radius + self.margin)
+    }
+}
+
+/// The Minkowski sum of two shapes, i.e. `shape1` swept over every point of `shape2`.
+#[derive(Clone, Debug)]
+pub struct MinkowskiSum<A, B> {
+    pub shape1: A,
+    pub shape2: B,
+}
+
+impl<A: SupportMap, B: SupportMap> MinkowskiSum<A, B> {
+    pub fn new(shape1: A, shape2: B) -> Self {
+        Self { shape1, shape2 }
+    }
+}
+
+impl<A: SupportMap, B: SupportMap> SupportMap for MinkowskiSum<A, B> {
+    fn support_point(&self, dir: DVec3) -> DVec3 {
+        self.shape1.support_point(dir) + self.shape2.support_point(dir)
+    }
+
+    fn margin(&self) -> f64 {
+        self.shape1.margin() + self.shape2.margin()
+    }
+
+    fn core_support_point(&self, dir: DVec3) -> DVec3 {
+        self.shape1.core_support_point(dir) + self.shape2.core_support_point(dir)
+    }
+
+    fn center(&self) -> Option<DVec3> {
+        Some(self.shape1.center()? + self.shape2.center()?)
+    }
+
+    fn bounding_radius(&self) -> Option<f64> {
+        Some(self.shape1.bounding_radius()? + self.shape2.bounding_radius()?)
+    }
+}
+
+/// Convex hull of several shapes, e.g. of a collider at several poses as swept volume.
+#[derive(Clone, Debug)]
+pub struct CompoundHull<S> {
+    pub shapes: Vec<S>,
+}
+
+impl<S: SupportMap> CompoundHull<S> {
+    pub fn new(shapes: Vec<S>) -> Self {
+        assert!(!shapes.is_empty(), "CompoundHull needs at least one shape");
+        Self { shapes }
+    }
+}
+
+impl CompoundHull<Collider> {
+    /// Hull of `collider` placed at each of the `poses`.
+    pub fn swept(collider: &Collider, poses: &[DMat4]) -> Self {
+        Self::new(poses.iter().map(|pose| collider.with_transform(*pose)).collect())
+    }
+}
+
+impl<S: SupportMap> SupportMap for CompoundHull<S> {
+    fn support_point(&self, dir: DVec3) -> DVec3 {
+        self.shapes.iter()
+            .map(|shape| shape.support_point(dir))
+            .max_by(|a, b| a.dot(dir).total_cmp(&b.dot(dir)))
+            .unwrap()
+    }
+
+    /// The smallest margin of all shapes, the remaining margins become part of the core.
+    fn margin(&self) -> f64 {
+        self.shapes.iter()
+            .map(|shape| shape.margin())
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    fn core_support_point(&self, dir: DVec3) -> DVec3 {
+        self.support_point(dir) - dir.normalize_or_zero() * self.margin()
+    }
+
+    fn center(&self) -> Option<DVec3> {
+        let mut sum = DVec3::ZERO;
+        for shape in self.shapes.iter() {
+            sum += shape.center()?;
+        }
+        Some(sum / self.shapes.len() as f64)
+    }
+
+    fn bounding_radius(&self) -> Option<f64> {
+        let center = self.center()?;
+        let mut radius = 0.0_f64;
+        for shape in self.shapes.iter() {
+            radius = radius.max(shape.center()?.distance(center) + shape.bounding_radius()?);
+        }
+        Some(radius)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::{dvec3, DMat4};
+
+    use crate::{colliders::Collider, composite::{CompoundHull, Inflated, MinkowskiSum}, epa::EPA, gjk::{GJKNesterov, GjkStatus}};
+
+    #[test]
+    fn test_inflated_distance() {
+        let collider1 = Inflated::new(Collider::new_box(DMat4::IDENTITY, dvec3(2.0, 2.0, 2.0)), 0.5);
+        let collider2 = Collider::new_sphere(DMat4::from_translation(dvec3(0.0, 0.0, 4.0)), 1.0);
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let result = gjk.distance_nesterov_accelerated(&collider1, &collider2, 100);
+        let points = gjk.closest_points();
+
+        assert!(result.status == GjkStatus::Separated);
+        assert!((result.distance - 1.5).abs() < 1e-9);
+        assert!(points.point1.distance(dvec3(0.0, 0.0, 1.5)) < 1e-9);
+        assert!(points.point2.distance(dvec3(0.0, 0.0, 3.0)) < 1e-9);
+    }
+
+    #[test]
+    fn test_minkowski_sum_of_box_and_sphere_is_rounded_box() {
+        let minkowski_sum = MinkowskiSum::new(
+            Collider::new_box(DMat4::IDENTITY, dvec3(1.0, 2.0, 3.0)),
+            Collider::new_sphere(DMat4::from_translation(dvec3(1.0, 0.0, 0.0)), 0.5));
+        let collider2 = Collider::new_box(DMat4::from_translation(dvec3(4.0, 0.0, 0.0)), dvec3(1.0, 1.0, 1.0));
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let result = gjk.distance_nesterov_accelerated(&minkowski_sum, &collider2, 100);
+
+        assert!(result.status == GjkStatus::Separated);
+        assert!((result.distance - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_penetration_of_crossing_capsules() {
+        let collider1 = Collider::new_capluse(DMat4::IDENTITY, 0.5, 4.0);
+        let collider2 = Collider::new_capluse(DMat4::from_rotation_x(std::f64::consts::FRAC_PI_2), 0.5, 4.0);
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let result = gjk.distance_nesterov_accelerated(&collider1, &collider2, 100);
+        assert!(result.status == GjkStatus::Overlapping);
+
+        let penetration = EPA::new(1e-6).penetration(&gjk, &collider1, &collider2, 1000).unwrap();
+        assert!((penetration.depth - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_swept_sphere_is_capsule() {
+        let sphere = Collider::new_sphere(DMat4::IDENTITY, 0.5);
+        let swept = CompoundHull::swept(&sphere, &[
+            DMat4::from_translation(dvec3(-2.0, 0.0, 0.0)),
+            DMat4::from_translation(dvec3(2.0, 0.0, 0.0)),
+        ]);
+        let collider2 = Collider::new_box(DMat4::from_translation(dvec3(1.0, 2.0, 0.0)), dvec3(1.0, 1.0, 1.0));
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let result = gjk.distance_nesterov_accelerated(&swept, &collider2, 100);
+        assert!(result.status == GjkStatus::Separated);
+        assert!((result.distance - 1.0).abs() < 1e-9);
+
+        let collider2 = Collider::new_box(DMat4::from_translation(dvec3(2.8, 0.0, 0.0)), dvec3(1.0, 1.0, 1.0));
+        let result = gjk.distance_nesterov_accelerated(&swept, &collider2, 100);
+        assert!(result.status == GjkStatus::Overlapping);
+    }
+}
@@ -0,0 +1,457 @@
+use glam::{DVec3, dvec3};
+
+use crate::{gjk::GJKNesterov, simplex::Vertex, support_map::SupportMap};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EpaStatus {
+    /// The closest face of the polytope is within the tolerance of the Minkowski difference.
+    Converged,
+    /// The polytope was still growing, `depth` is only a lower bound and `normal` an approximation.
+    MaxIterationsReached,
+}
+
+/// Penetration of two intersecting colliders.
+#[derive(Copy, Clone, Debug)]
+pub struct Penetration {
+    pub status: EpaStatus,
+    /// Distance `collider2` has to be moved along `normal` to separate the colliders,
+    /// negative if they are already separated.
+    pub depth: f64,
+    /// Unit contact normal pointing from `collider1` to `collider2`.
+    pub normal: DVec3,
+    /// Deepest point of `collider1` inside `collider2`.
+    pub point1: DVec3,
+    /// Deepest point of `collider2` inside `collider1`.
+    pub point2: DVec3,
+    pub iterations: usize,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Face {
+    indices: [usize; 3],
+    normal: DVec3,
+    distance: f64,
+}
+
+pub struct EPA
+{
+    tolerance: f64,
+
+    vertices: Vec<Vertex>,
+    faces: Vec<Face>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl EPA
+{
+    pub fn new(tolerance: f64) -> Self {
+        Self {
+            tolerance,
+            vertices: Vec::new(),
+            faces: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Expands the terminating simplex of an intersecting `gjk` query into the penetration of both colliders.
+    /// Returns `None` if the Minkowski difference is flat and no tetrahedron can be built from it.
+    pub fn penetration<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(
+        &mut self,
+        gjk: &GJKNesterov,
+        collider1: &A,
+        collider2: &B,
+        max_iterations: usize,
+    ) -> Option<Penetration> {
+        let (margin1, margin2) = gjk.margins();
+        let inflation = margin1 + margin2;
+
+        let core_distance = gjk.ray().length();
+        if core_distance > self.tolerance {
+            // Only the margins overlap, the closest points of the cores give the penetration.
+            let (point1, point2) = gjk.core_closest_points();
+            let normal = -gjk.ray() / core_distance;
+
+            return Some(Penetration {
+                depth: inflation - core_distance,
+                normal,
+                point1: point1 + normal * margin1,
+                point2: point2 - normal * margin2,
+                status: EpaStatus::Converged,
+                iterations: 0,
+            });
+        }
+
+        if let Some(mut penetration) = self.expand_polytope(gjk.simplex(), collider1, collider2, max_iterations, false) {
+            penetration.depth += inflation;
+            penetration.point1 += penetration.normal * margin1;
+            penetration.point2 -= penetration.normal * margin2;
+            return Some(penetration);
+        }
+
+        if inflation == 0.0 {
+            return None;
+        }
+
+        // The cores are flat, e.g. two crossing capsule segments, so the full shapes are expanded instead.
+        self.expand_polytope(gjk.simplex(), collider1, collider2, max_iterations, true)
+    }
+
+    fn expand_polytope<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(
+        &mut self,
+        simplex: &[Vertex],
+        collider1: &A,
+        collider2: &B,
+        max_iterations: usize,
+        with_margin: bool,
+    ) -> Option<Penetration> {
+        self.vertices.clear();
+        self.faces.clear();
+        self.vertices.extend_from_slice(simplex);
+
+        if !self.blow_up_simplex(collider1, collider2, with_margin) {
+            return None;
+        }
+
+        for (i, j, k, l) in [(0, 1, 2, 3), (0, 3, 1, 2), (0, 2, 3, 1), (1, 3, 2, 0)] {
+            self.add_face_facing_away(i, j, k, l);
+        }
+
+        let mut status = EpaStatus::MaxIterationsReached;
+        let mut iterations = max_iterations;
+        let mut closest = self.closest_face();
+        for i in 0..max_iterations {
+            let face = self.faces[closest];
+            let support_point = support(collider1, collider2, face.normal, with_margin);
+
+            let converged = face.normal.dot(support_point.v) - face.distance < self.tolerance;
+
+            // No face is visible from a support point beyond the closest face only through rounding errors,
+            // the polytope cannot grow any further then.
+            if converged || !self.expand_with(support_point) {
+                status = EpaStatus::Converged;
+                iterations = i;
+                break;
+            }
+            closest = self.closest_face();
+        }
+
+        let face = self.faces[closest];
+        let barycentric = self.face_barycentric(&face);
+
+        let mut point1 = DVec3::ZERO;
+        let mut point2 = DVec3::ZERO;
+        for (index, weight) in face.indices.iter().zip(barycentric) {
+            point1 += weight * self.vertices[*index].s0;
+            point2 += weight * self.vertices[*index].s1;
+        }
+
+        Some(Penetration {
+            status,
+            depth: face.distance,
+            normal: face.normal,
+            point1,
+            point2,
+            iterations,
+        })
+    }
+
+    /// Adds support points until the polytope is a tetrahedron with non zero volume.
+    fn blow_up_simplex<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(&mut self, collider1: &A, collider2: &B, with_margin: bool) -> bool {
+        let eps = self.tolerance;
+
+        if self.vertices.len() == 4 {
+            let [a, b, c, d] = [0, 1, 2, 3].map(|i| self.vertices[i].v);
+            if (b - a).dot((c - a).cross(d - a)).abs() > eps * eps * eps {
+                return true;
+            }
+            self.vertices.truncate(3);
+        }
+
+        if self.vertices.len() == 3 {
+            let [a, b, c] = [0, 1, 2].map(|i| self.vertices[i].v);
+            if (b - a).cross(c - a).length_squared() <= eps * eps * eps * eps {
+                self.vertices.truncate(2);
+            }
+        }
+
+        if self.vertices.len() == 2 && (self.vertices[1].v - self.vertices[0].v).length() <= eps {
+            self.vertices.truncate(1);
+        }
+
+        if self.vertices.is_empty() {
+            self.vertices.push(support(collider1, collider2, dvec3(1.0, 0.0, 0.0), with_margin));
+        }
+
+        if self.vertices.len() == 1 {
+            let a = self.vertices[0].v;
+            let axes = [DVec3::X, DVec3::Y, DVec3::Z, -DVec3::X, -DVec3::Y, -DVec3::Z];
+            match axes.iter()
+                .map(|axis| support(collider1, collider2, *axis, with_margin))
+                .find(|vertex| (vertex.v - a).length() > eps) {
+                Some(vertex) => self.vertices.push(vertex),
+                None => return false,
+            }
+        }
+
+        if self.vertices.len() == 2 {
+            let a = self.vertices[0].v;
+            let ab = (self.vertices[1].v - a).normalize();
+            let axis = if ab.x.abs() < 0.57735 { DVec3::X } else if ab.y.abs() < 0.57735 { DVec3::Y } else { DVec3::Z };
+            let perpendicular = ab.cross(axis).normalize();
+
+            let found = (0..6)
+                .map(|i| {
+                    let angle = i as f64 * std::f64::consts::FRAC_PI_3;
+                    let dir = perpendicular * angle.cos() + ab.cross(perpendicular) * angle.sin();
+                    support(collider1, collider2, dir, with_margin)
+                })
+                .find(|vertex| (vertex.v - a).cross(ab).length() > eps);
+            match found {
+                Some(vertex) => self.vertices.push(vertex),
+                None => return false,
+            }
+        }
+
+        if self.vertices.len() == 3 {
+            let [a, b, c] = [0, 1, 2].map(|i| self.vertices[i].v);
+            let normal = (b - a).cross(c - a).normalize();
+
+            let found = [normal, -normal].iter()
+                .map(|dir| support(collider1, collider2, *dir, with_margin))
+                .find(|vertex| (vertex.v - a).dot(normal).abs() > eps);
+            match found {
+                Some(vertex) => self.vertices.push(vertex),
+                None => return false,
+            }
+        }
+
+        true
+    }
+
+    fn add_face_facing_away(&mut self, i: usize, j: usize, k: usize, opposite: usize) {
+        let a = self.vertices[i].v;
+        let normal = (self.vertices[j].v - a).cross(self.vertices[k].v - a);
+        if normal.dot(self.vertices[opposite].v - a) > 0.0 {
+            self.add_face(i, k, j);
+        } else {
+            self.add_face(i, j, k);
+        }
+    }
+
+    fn add_face(&mut self, i: usize, j: usize, k: usize) {
+        let a = self.vertices[i].v;
+        let normal = (self.vertices[j].v - a).cross(self.vertices[k].v - a).normalize_or_zero();
+
+        // Degenerate faces are kept to close the polytope but are never chosen as closest face.
+        let distance = if normal == DVec3::ZERO { f64::INFINITY } else { normal.dot(a) };
+
+        self.faces.push(Face { indices: [i, j, k], normal, distance });
+    }
+
+    fn closest_face(&self) -> usize {
+        self.faces.iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.distance.total_cmp(&b.distance))
+            .map(|(i, _)| i)
+            .unwrap()
+    }
+
+    /// Adds a vertex, removes all faces visible from it and connects it to the horizon.
+    fn expand_with(&mut self, vertex: Vertex) -> bool {
+        self.vertices.push(vertex);
+        let new_index = self.vertices.len() - 1;
+        let w = vertex.v;
+
+        self.edges.clear();
+        let mut i = 0;
+        while i < self.faces.len() {
+            let face = self.faces[i];
+            if face.normal.dot(w - self.vertices[face.indices[0]].v) <= 0.0 {
+                i += 1;
+                continue;
+            }
+
+            for e in 0..3 {
+                let edge = (face.indices[e], face.indices[(e + 1) % 3]);
+                match self.edges.iter().position(|other| *other == (edge.1, edge.0)) {
+                    Some(position) => { self.edges.swap_remove(position); },
+                    None => self.edges.push(edge),
+                }
+            }
+            self.faces.swap_remove(i);
+        }
+
+        if self.edges.is_empty() {
+            return false;
+        }
+
+        for e in 0..self.edges.len() {
+            let (a, b) = self.edges[e];
+            self.add_face(a, b, new_index);
+        }
+
+        true
+    }
+
+    fn face_barycentric(&self, face: &Face) -> [f64; 3] {
+        let [a, b, c] = face.indices.map(|i| self.vertices[i].v);
+        let p = face.normal * face.distance;
+
+        let n = (b - a).cross(c - a);
+        let n_len2 = n.length_squared();
+        if n_len2 == 0.0 {
+            return [1.0, 0.0, 0.0];
+        }
+
+        let u = (b - p).cross(c - p).dot(n) / n_len2;
+        let v = (c - p).cross(a - p).dot(n) / n_len2;
+        [u, v, 1.0 - u - v]
+    }
+}
+
+fn support<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(collider1: &A, collider2: &B, dir: DVec3, with_margin: bool) -> Vertex {
+    if with_margin {
+        Vertex::new(collider1.support_point(dir), collider2.support_point(-dir))
+    } else {
+        Vertex::new(collider1.core_support_point(dir), collider2.core_support_point(-dir))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::{dvec3, DMat4};
+
+    use crate::{colliders::Collider, gjk::{GJKNesterov, GjkStatus}, epa::{EpaStatus, Penetration, EPA}};
+
+    fn penetration(collider1: &Collider, collider2: &Collider) -> Penetration {
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let result = gjk.distance_nesterov_accelerated(collider1, collider2, 100);
+        assert!(result.status == GjkStatus::Overlapping);
+
+        let penetration = EPA::new(1e-6).penetration(&gjk, collider1, collider2, 1000).unwrap();
+        assert!(penetration.status == EpaStatus::Converged);
+        penetration
+    }
+
+    #[test]
+    fn test_penetration_boxes() {
+        let collider1 = Collider::new_box(DMat4::IDENTITY, dvec3(2.0, 2.0, 2.0));
+        let collider2 = Collider::new_box(DMat4::from_translation(dvec3(1.5, 0.2, 0.1)), dvec3(2.0, 2.0, 2.0));
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let result = gjk.distance_nesterov_accelerated(&collider1, &collider2, 100);
+        assert!(result.status == GjkStatus::Overlapping);
+
+        let penetration = EPA::new(1e-6).penetration(&gjk, &collider1, &collider2, 100).unwrap();
+        assert!((penetration.depth - 0.5).abs() < 1e-6);
+        assert!(penetration.normal.distance(dvec3(1.0, 0.0, 0.0)) < 1e-6);
+        assert!((penetration.point1.x - 1.0).abs() < 1e-6);
+        assert!((penetration.point2.x - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_penetration_spheres() {
+        let collider1 = Collider::new_sphere(DMat4::IDENTITY, 1.0);
+        let collider2 = Collider::new_sphere(DMat4::from_translation(dvec3(0.0, 1.5, 0.0)), 1.0);
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let result = gjk.distance_nesterov_accelerated(&collider1, &collider2, 100);
+        assert!(result.status == GjkStatus::Overlapping);
+
+        let penetration = EPA::new(1e-6).penetration(&gjk, &collider1, &collider2, 100).unwrap();
+        assert!((penetration.depth - 0.5).abs() < 1e-3);
+        assert!(penetration.normal.distance(dvec3(0.0, 1.0, 0.0)) < 1e-2);
+    }
+
+    #[test]
+    fn test_penetration_capsules() {
+        // Parallel cores, only the margins overlap.
+        let collider1 = Collider::new_capluse(DMat4::IDENTITY, 0.5, 2.0);
+        let collider2 = Collider::new_capluse(DMat4::from_translation(dvec3(0.8, 0.0, 0.0)), 0.5, 2.0);
+
+        let penetration = penetration(&collider1, &collider2);
+        assert!((penetration.depth - 0.2).abs() < 1e-6);
+        assert!(penetration.normal.distance(dvec3(1.0, 0.0, 0.0)) < 1e-6);
+        assert!((penetration.point1.x - 0.5).abs() < 1e-6);
+        assert!((penetration.point2.x - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_penetration_cylinders() {
+        let collider1 = Collider::new_cylinder(DMat4::IDENTITY, 1.0, 2.0);
+        let collider2 = Collider::new_cylinder(DMat4::from_translation(dvec3(0.2, 0.0, 1.7)), 1.0, 2.0);
+
+        let penetration = penetration(&collider1, &collider2);
+        assert!((penetration.depth - 0.3).abs() < 1e-6);
+        assert!(penetration.normal.distance(dvec3(0.0, 0.0, 1.0)) < 1e-6);
+    }
+
+    #[test]
+    fn test_penetration_cone() {
+        // The base of the cone sinks into the top of the box.
+        let collider1 = Collider::new_box(DMat4::IDENTITY, dvec3(4.0, 4.0, 2.0));
+        let collider2 = Collider::new_cone(DMat4::from_translation(dvec3(0.0, 0.0, 1.8)), 1.0, 2.0);
+
+        let penetration = penetration(&collider1, &collider2);
+        assert!((penetration.depth - 0.2).abs() < 1e-6);
+        assert!(penetration.normal.distance(dvec3(0.0, 0.0, 1.0)) < 1e-6);
+        assert!((penetration.point1.z - 1.0).abs() < 1e-6);
+        assert!((penetration.point2.z - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_penetration_ellipsoid() {
+        // The tip of the ellipsoid pokes into the bottom of the box.
+        let collider1 = Collider::new_ellipsoid(DMat4::IDENTITY, dvec3(2.0, 4.0, 6.0));
+        let collider2 = Collider::new_box(DMat4::from_translation(dvec3(0.0, 2.7, 0.0)), dvec3(2.0, 2.0, 2.0));
+
+        let penetration = penetration(&collider1, &collider2);
+        assert!((penetration.depth - 0.3).abs() < 1e-3);
+        assert!(penetration.normal.distance(dvec3(0.0, 1.0, 0.0)) < 1e-2);
+        assert!(penetration.point1.distance(dvec3(0.0, 2.0, 0.0)) < 1e-2);
+    }
+
+    #[test]
+    fn test_penetration_rounded_boxes() {
+        // The cores overlap, the margins add to the depth of the cores.
+        let collider1 = Collider::new_rounded_box(DMat4::IDENTITY, dvec3(2.0, 2.0, 2.0), 0.3);
+        let collider2 = Collider::new_rounded_box(DMat4::from_translation(dvec3(1.2, 0.1, 0.0)), dvec3(2.0, 2.0, 2.0), 0.3);
+
+        let penetration = penetration(&collider1, &collider2);
+        assert!((penetration.depth - 0.8).abs() < 1e-6);
+        assert!(penetration.normal.distance(dvec3(1.0, 0.0, 0.0)) < 1e-6);
+        assert!((penetration.point1.x - 1.0).abs() < 1e-6);
+        assert!((penetration.point2.x - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_penetration_convex_hull() {
+        // A vertex of the octahedron pokes into the side of the box.
+        let vertices = [
+            dvec3(1.0, 0.0, 0.0), dvec3(-1.0, 0.0, 0.0),
+            dvec3(0.0, 1.0, 0.0), dvec3(0.0, -1.0, 0.0),
+            dvec3(0.0, 0.0, 1.0), dvec3(0.0, 0.0, -1.0),
+        ];
+        let collider1 = Collider::new_convex_hull(DMat4::IDENTITY, &vertices);
+        let collider2 = Collider::new_box(DMat4::from_translation(dvec3(1.8, 0.0, 0.0)), dvec3(2.0, 2.0, 2.0));
+
+        let penetration = penetration(&collider1, &collider2);
+        assert!((penetration.depth - 0.2).abs() < 1e-6);
+        assert!(penetration.normal.distance(dvec3(1.0, 0.0, 0.0)) < 1e-6);
+        assert!(penetration.point1.distance(dvec3(1.0, 0.0, 0.0)) < 1e-6);
+        assert!((penetration.point2.x - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_penetration_not_converged() {
+        // Curved shapes without margin need many iterations to converge.
+        let collider1 = Collider::new_ellipsoid(DMat4::IDENTITY, dvec3(2.0, 2.0, 2.0));
+        let collider2 = Collider::new_ellipsoid(DMat4::from_translation(dvec3(0.0, 1.5, 0.0)), dvec3(2.0, 2.0, 2.0));
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        gjk.distance_nesterov_accelerated(&collider1, &collider2, 100);
+        let penetration = EPA::new(1e-9).penetration(&gjk, &collider1, &collider2, 2).unwrap();
+        assert!(penetration.status == EpaStatus::MaxIterationsReached);
+        assert!(penetration.iterations == 2);
+    }
+}
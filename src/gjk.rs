@@ -1,561 +1,784 @@
+use std::marker::PhantomData;
+
 use glam::{DVec3, dvec3};
 
-use crate::colliders::Collider;
+use crate::{scalar::{Scalar, Vector3}, simplex::{Simplex, Vertex}, support_map::SupportMap};
 
-#[allow(dead_code)]
-#[derive(Copy, Clone, Debug)]
-struct Vertex {
-    v: DVec3,
-    s0: DVec3,
-    s1: DVec3,
+/// When a distance query has converged, in terms of the duality gap `|ray| - alpha`
+/// between the upper bound `|ray|` and the best lower bound `alpha` of the distance.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ConvergenceCriterion {
+    /// `|ray| - alpha <= tolerance * |ray|`, the error scales with the distance.
+    Relative,
+    /// `|ray| - alpha <= tolerance`.
+    Absolute,
+}
+
+impl ConvergenceCriterion {
+    pub(crate) fn is_converged(&self, upper_bound: f64, lower_bound: f64, tolerance: f64) -> bool {
+        let diff = upper_bound - lower_bound;
+
+        match self {
+            ConvergenceCriterion::Relative => (diff - tolerance * upper_bound) <= 0.0,
+            ConvergenceCriterion::Absolute => (diff - tolerance) <= 0.0,
+        }
+    }
+}
+
+/// Algorithm that reduces the simplex to its sub simplex closest to the origin in every iteration.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SubDistance {
+    /// Branchy Voronoi region tests in the style of Johnson's algorithm, which expect the newest vertex
+    /// to be part of the closest feature and fall back to checking all sub simplices otherwise.
+    Johnson,
+    /// Signed volumes of Montanari et al., robust for any vertex order.
+    SignedVolumes,
 }
 
+/// Common interface of the distance solvers, to compare them on the same colliders.
+pub trait DistanceSolver {
+    fn distance<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(
+        &mut self,
+        collider1: &A,
+        collider2: &B,
+        max_iterations: usize,
+    ) -> GjkResult;
+
+    /// Witness points of the last query.
+    fn closest_points(&self) -> ClosestPoints;
+}
+
+/// Options of the distance solver.
+#[derive(Clone, Copy, Debug)]
+pub struct GjkConfig {
+    pub tolerance: f64,
+    /// Accelerates the search direction with Nesterov momentum until the Frank-Wolfe duality gap is small,
+    /// plain GJK if `false`.
+    pub use_nesterov_acceleration: bool,
+    /// Normalizes the accelerated search direction in every iteration.
+    pub normalize_support_direction: bool,
+    /// The query stops as soon as the lower bound of the distance exceeds this.
+    pub upper_bound: f64,
+    /// Radius the colliders are inflated by in total on top of their margins.
+    /// Only the reported distance and status change, the closest points stay on the colliders.
+    pub inflation: f64,
+    pub convergence_criterion: ConvergenceCriterion,
+    pub sub_distance: SubDistance,
+}
 
-pub struct GJKNesterov
+impl Default for GjkConfig {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-6,
+            use_nesterov_acceleration: true,
+            normalize_support_direction: false,
+            upper_bound: 1000000000.0,
+            inflation: 0.0,
+            convergence_criterion: ConvergenceCriterion::Relative,
+            sub_distance: SubDistance::Johnson,
+        }
+    }
+}
+
+impl GjkConfig {
+    /// Tolerance that single precision colliders can resolve, see `GJKNesterov<f32>`.
+    pub fn for_f32() -> Self {
+        Self {
+            tolerance: 1e-4,
+            ..Default::default()
+        }
+    }
+}
+
+/// Distance solver that can be reused for any number of queries and collider pairs.
+///
+/// `GJKNesterov<f32>` queries shapes that implement `SupportMap<f32>`, like `Collider<f32>`. Their support points
+/// are widened to `f64` exactly and the simplex is solved in `f64`, so both precisions share this implementation.
+/// Use `GjkConfig::for_f32` as tolerances.
+pub struct GJKNesterov<F: Scalar = f64>
 {
+    // Configuration, kept between queries.
+    config: GjkConfig,
+    ray_guess: DVec3,
+    /// Simplex of a previous query the next query starts from, consumed like the ray guess.
+    warm_simplex: GjkCache,
+
+    // State of the current query, rebuilt by `reset` at the start of every query.
+    use_nesterov_acceleration: bool,
     alpha: f64,
     omega: f64,
-    tolerance: f64,
 
-    simplex: [Vertex; 4],
-    simplex_len: usize,
+    simplex: Simplex,
     ray: DVec3,
     ray_len: f64,
     ray_dir: DVec3,
 
     support_point: Vertex,
+
+    margin1: f64,
+    margin2: f64,
+    inflation: f64,
+
+    scalar: PhantomData<F>,
 }
 
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GjkStatus {
+    /// The colliders are disjoint, `distance` is their distance.
+    Separated,
+    /// The colliders are closer than the tolerance.
+    Touching,
+    /// The simplex encloses the origin, the colliders intersect.
+    Overlapping,
+    /// The query did not converge, `distance` is only an upper bound.
+    MaxIterationsReached,
+}
 
+#[derive(Clone, Copy, Debug)]
+pub struct GjkResult {
+    pub status: GjkStatus,
+    pub distance: f64,
+    pub iterations: usize,
+    /// Final closest point of the Minkowski difference `collider1 - collider2` to the origin.
+    pub ray: DVec3,
+    pub simplex_len: usize,
+}
 
-impl GJKNesterov
-{
-    pub fn new(ray_guess: Option<DVec3>, tolerance: f64) -> Self {
+impl GjkResult {
+    /// Whether the colliders touch or overlap.
+    pub fn inside(&self) -> bool {
+        matches!(self.status, GjkStatus::Touching | GjkStatus::Overlapping)
+    }
+}
 
-        let simplex = [Vertex::default(), Vertex::default(), Vertex::default(), Vertex::default()];
-        let mut ray = ray_guess.unwrap_or(dvec3(1.0, 0.0, 0.0));
-        let mut ray_len = ray.length();
+/// Result of a boolean intersection test.
+#[derive(Clone, Copy, Debug)]
+pub struct Intersection {
+    /// Whether the colliders intersect, also true if the test ran out of iterations without finding a separating axis.
+    pub intersects: bool,
+    /// Unit direction from `collider1` to `collider2` along which the colliders are separated, zero if they intersect
+    /// or the test ran out of iterations.
+    pub separating_axis: DVec3,
+    pub iterations: usize,
+}
 
-        if ray_len < tolerance {
-            ray = dvec3(1.0, 0.0, 0.0);
-            ray_len = 1.0;
-        }
+/// Vertex of the final simplex of a query, see `GjkCache`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CachedVertex {
+    /// Direction the support point of `collider2` was searched in, `collider1` was searched in the opposite one.
+    pub dir: DVec3,
+    /// Support point on the core of `collider1` at the time of the query.
+    pub point1: DVec3,
+    /// Support point on the core of `collider2` at the time of the query.
+    pub point2: DVec3,
+    /// Index of `point1` among the vertices of `collider1`, see `SupportMap::core_support_vertex`.
+    pub index1: Option<usize>,
+    /// Index of `point2` among the vertices of `collider2`.
+    pub index2: Option<usize>,
+}
 
-        let ray_dir = ray;
-        let support_point = Vertex { v: ray, s0: ray, s1: ray };
+/// State of a collider pair that is kept between frames to warm start the next query.
+///
+/// The final simplex of the last query and its closest point barely move for temporally coherent motion.
+/// The next query rebuilds the simplex from the cached vertices before its first iteration, so it often
+/// only needs to confirm the distance. The cached points themselves are stale once the colliders moved,
+/// so every vertex is looked up again, by its index for shapes with vertices like convex hulls and by its
+/// search direction otherwise. The rebuilt simplex therefore always lies in the current Minkowski difference.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GjkCache {
+    /// Closest point of `collider1 - collider2` to the origin, zero if the cache is empty.
+    pub ray: DVec3,
+    /// Vertices of the final simplex, only the first `len` are used.
+    pub vertices: [CachedVertex; 4],
+    pub len: usize,
+}
 
-        Self {
+/// Closest points between two colliders after a distance query.
+#[derive(Copy, Clone, Debug)]
+pub struct ClosestPoints {
+    /// Closest point on `collider1`.
+    pub point1: DVec3,
+    /// Closest point on `collider2`.
+    pub point2: DVec3,
+    /// Unit direction from `point1` to `point2`, zero if the colliders intersect.
+    pub normal: DVec3,
+}
+
+
+
+impl<F: Scalar> GJKNesterov<F>
+{
+    pub fn new(ray_guess: Option<DVec3>, tolerance: f64) -> Self {
+        Self::with_config(ray_guess, GjkConfig { tolerance, ..Default::default() })
+    }
+
+    pub fn with_config(ray_guess: Option<DVec3>, config: GjkConfig) -> Self {
+        let mut gjk = Self {
+            config,
+            ray_guess: DVec3::ZERO,
+            warm_simplex: GjkCache::default(),
+            use_nesterov_acceleration: config.use_nesterov_acceleration,
             alpha: 0.0,
             omega: 0.0,
-            tolerance,
-            simplex,
-            simplex_len: 0,
-            ray,
-            ray_len,
-            ray_dir,
-            support_point,
+            simplex: Simplex::new(),
+            ray: DVec3::ZERO,
+            ray_len: 0.0,
+            ray_dir: DVec3::ZERO,
+            support_point: Vertex::default(),
+            margin1: 0.0,
+            margin2: 0.0,
+            inflation: 0.0,
+            scalar: PhantomData,
+        };
+        gjk.set_ray_guess(ray_guess);
+        gjk
+    }
+
+    pub fn config(&self) -> &GjkConfig {
+        &self.config
+    }
+
+    /// Initial search direction of the next query, the x axis if `None` or shorter than the tolerance.
+    /// The guess belongs to a single pair and is consumed by that query, later queries start from the x axis again.
+    pub fn set_ray_guess(&mut self, ray_guess: Option<DVec3>) {
+        self.ray_guess = match ray_guess {
+            Some(ray) if ray.length() >= self.config.tolerance => ray,
+            _ => dvec3(1.0, 0.0, 0.0),
+        };
+        self.reset();
+    }
+
+    /// Clears the state of the last query. Every query starts with a reset, so the results of
+    /// the last query (closest points, cache) are only available until the next one.
+    pub fn reset(&mut self) {
+        self.use_nesterov_acceleration = self.config.use_nesterov_acceleration;
+        self.alpha = 0.0;
+        self.omega = 0.0;
+        self.simplex = Simplex::new();
+        self.ray = self.ray_guess;
+        self.ray_len = self.ray.length();
+        self.ray_dir = self.ray;
+        self.support_point = Vertex { v: self.ray, s0: self.ray, s1: self.ray, ..Vertex::default() };
+        self.margin1 = 0.0;
+        self.margin2 = 0.0;
+        self.inflation = 0.0;
+    }
+
+    /// Resets the solver for a query and consumes the ray guess and the warm start simplex,
+    /// so they do not leak into queries of other pairs.
+    fn start_query(&mut self) {
+        self.reset();
+        self.ray_guess = dvec3(1.0, 0.0, 0.0);
+        self.warm_simplex = GjkCache::default();
+    }
+
+    /// Starts the next query from the state of a previous query of the same pair, see [`GjkCache`].
+    pub fn with_cache(cache: &GjkCache, tolerance: f64) -> Self {
+        let mut gjk = Self::new(None, tolerance);
+        gjk.warm_start(cache);
+        gjk
+    }
+
+    /// Like `with_cache` for an existing solver, the next query starts from `cache`.
+    pub fn warm_start(&mut self, cache: &GjkCache) {
+        self.set_ray_guess(Some(cache.ray));
+        self.warm_simplex = *cache;
+    }
+
+    /// State of the last query to warm start the next query of the same pair with `with_cache`.
+    pub fn cache(&self) -> GjkCache {
+        let mut cache = GjkCache { ray: self.ray, len: self.simplex.len, ..Default::default() };
+        for (cached, vertex) in cache.vertices.iter_mut().zip(self.simplex.vertices()) {
+            *cached = CachedVertex {
+                dir: vertex.dir,
+                point1: vertex.s0,
+                point2: vertex.s1,
+                index1: vertex.index0,
+                index2: vertex.index1,
+            };
         }
+        cache
     }
 
-    pub fn distance_nesterov_accelerated(&mut self, collider1: &Collider, collider2: &Collider, max_iterations: usize) -> (bool, f64, usize){
-        let upper_bound = 1000000000.0;
-    
-        let mut use_nesterov_acceleration = true;
-        let normalize_support_direction = false;
-    
-        let inflation = 0.0;
-    
-        let mut inside = false;
-        let mut distance = 0.0;
-        let mut interation = 0;
-    
-        for i in 0..max_iterations {
-            interation = i;
-            let k = i as f64;
-    
-            if self.ray_len < self.tolerance {
-                distance = -inflation;
-                inside = true;
-                break;
-            }
-    
-            if use_nesterov_acceleration {
-                let momentum = (k + 1.0) / (k + 3.0);
-                let y = momentum * self.ray + (1.0 - momentum) * self.support_point.v;
-                self.ray_dir = momentum * self.ray_dir + (1.0 - momentum) * y;
-    
-                if normalize_support_direction {
-                    self.ray_dir = self.ray_dir.normalize();
-                }
-            } else {
-                self.ray_dir = self.ray;
-            }
-    
-            let s0 = collider1.get_support_point(-self.ray_dir);
-            let s1 = collider2.get_support_point(self.ray_dir);
-            self.support_point = Vertex::new(s0, s1);
-    
-            self.simplex[self.simplex_len] = self.support_point;
-            self.simplex_len += 1;
-    
-            self.omega = self.ray_dir.dot(self.support_point.v) / self.ray_dir.length();
-            if self.omega > upper_bound {
-                distance = self.omega - inflation;
-                inside = false;
-                break;
-            }
-    
-            if use_nesterov_acceleration {
-                let frank_wolfe_duality_gap = 2.0 * self.ray.dot(self.ray - self.support_point.v);
-                if frank_wolfe_duality_gap - self.tolerance <= 0.0 {
-                    use_nesterov_acceleration = false;
-                    self.simplex_len -= 1;
-                    continue;
-                }
-            }
-    
-            let cv_check_passed = self.check_convergence();
-            if i > 0 && cv_check_passed {
-                self.simplex_len -= 1;
+    pub fn distance_nesterov_accelerated<A: SupportMap<F> + ?Sized, B: SupportMap<F> + ?Sized>(
+        &mut self,
+        collider1: &A,
+        collider2: &B,
+        max_iterations: usize,
+    ) -> GjkResult {
+        let warm_simplex = self.warm_simplex;
 
-                if use_nesterov_acceleration {
-                    use_nesterov_acceleration = false;
-                    continue;
-                }
-                distance = self.ray_len - inflation;
-    
-                if distance < self.tolerance {
-                    inside = true
-                }
-                break;
+        // Seed the simplex with the support point along the guess so the ray is a point of the Minkowski difference.
+        let seed = match warm_simplex.len {
+            0 => Self::support(collider1, collider2, self.ray_guess),
+            _ => Self::cached_support(collider1, collider2, &warm_simplex.vertices[0]),
+        };
+        self.start(collider1.margin().to_f64(), collider2.margin().to_f64(), seed);
+
+        if warm_simplex.len > 1 {
+            for cached in warm_simplex.vertices[1..warm_simplex.len].iter() {
+                self.simplex.push(Self::cached_support(collider1, collider2, cached));
             }
-    
-            match self.simplex_len {
-                1 => {
-                    self.ray = self.support_point.v;
-                }
-                2 => inside = self.project_line_origen(),
-                3 => inside = self.project_triangle_origen(),
-                4 => inside = self.project_tetra_to_origen(),
-                _ => {}
+
+            let inside = self.simplex.project(self.config.sub_distance);
+            self.ray = self.simplex.ray;
+            self.ray_len = self.ray.length();
+            self.ray_dir = self.ray;
+
+            if inside {
+                return self.result(GjkStatus::Overlapping, -self.inflation, 0);
             }
-    
-            if !inside {
-                self.ray_len = self.ray.length();
+        }
+
+        for i in 0..max_iterations {
+            if let Some(result) = self.begin_iteration(i) {
+                return result;
             }
-    
-            if inside || self.ray_len == 0.0 {
-                distance = -inflation;
-                inside = true;
-                break;
+
+            let support_point = Self::support(collider1, collider2, self.ray_dir);
+            if let Some(result) = self.step(i, support_point) {
+                return result;
             }
         }
-    
-        return (inside, distance, interation);
-    }
-    
-    fn check_convergence(&mut self) -> bool {
-        self.alpha = self.alpha.max(self.omega);
-    
-        let diff = self.ray_len - self.alpha;
-    
-        return (diff - self.tolerance * self.ray_len) <= 0.0;
+
+        self.unfinished(max_iterations)
     }
-    
-    fn origen_to_point(&mut self, a_index: usize, a: DVec3) {
-        self.ray = a;
-        self.simplex[0] = self.simplex[a_index];
-        self.simplex_len = 1;
+
+    /// Direction the support point `seed` of the next query is searched in, see `search_direction`.
+    pub(crate) fn ray_guess(&self) -> DVec3 {
+        self.ray_guess
     }
-    
-    fn origen_to_segment(
-        &mut self,
-        a_index: usize,
-        b_index: usize,
-        a: DVec3,
-        b: DVec3,
-        ab: DVec3,
-        ab_dot_a0: f64,
-    ) {
-        self.ray = (ab.dot(b) * a + ab_dot_a0 * b) / ab.length_squared();
-        self.simplex[0] = self.simplex[b_index];
-        self.simplex[1] = self.simplex[a_index];
-        self.simplex_len = 2;
+
+    /// Starts a query of the cores with the given margins from the support point `seed` along the ray guess.
+    ///
+    /// `distance_nesterov_accelerated` is `start` followed by `begin_iteration` and `step` in every iteration,
+    /// callers that evaluate the support points themselves, like `distance_lockstep`, drive the query with them.
+    pub(crate) fn start(&mut self, margin1: f64, margin2: f64, seed: Vertex) {
+        self.start_query();
+
+        // The simplex is built on the core shapes, their margins are added back afterwards.
+        self.margin1 = margin1;
+        self.margin2 = margin2;
+        self.inflation = margin1 + margin2 + self.config.inflation;
+        self.use_nesterov_acceleration = self.config.use_nesterov_acceleration;
+
+        self.support_point = seed;
+        self.simplex.set(seed);
+        self.ray = seed.v;
+        self.ray_len = self.ray.length();
+        self.ray_dir = self.ray;
     }
-    
-    fn origen_to_triangle(
-        &mut self,
-        a_index: usize,
-        b_index: usize,
-        c_index: usize,
-        abc: DVec3,
-        abc_dot_a0: f64,
-    ) -> bool {
-        if abc_dot_a0 == 0.0 {
-            self.simplex[0] = self.simplex[c_index];
-            self.simplex[1] = self.simplex[b_index];
-            self.simplex[2] = self.simplex[a_index];
-            self.simplex_len = 3;
-    
-            self.ray = DVec3::ZERO;
-            return true;
+
+    /// Finishes the query in iteration `i` if the cores touch, otherwise updates the search direction.
+    pub(crate) fn begin_iteration(&mut self, i: usize) -> Option<GjkResult> {
+        if self.ray_len < self.config.tolerance {
+            let status = if self.inflation > self.config.tolerance {
+                GjkStatus::Overlapping
+            } else {
+                GjkStatus::Touching
+            };
+            return Some(self.result(status, -self.inflation, i));
         }
-    
-        if abc_dot_a0 > 0.0 {
-            self.simplex[0] = self.simplex[c_index];
-            self.simplex[1] = self.simplex[b_index];
+
+        if self.use_nesterov_acceleration {
+            let momentum = (i as f64 + 1.0) / (i as f64 + 3.0);
+            let y = momentum * self.ray + (1.0 - momentum) * self.support_point.v;
+            self.ray_dir = momentum * self.ray_dir + (1.0 - momentum) * y;
+
+            if self.config.normalize_support_direction {
+                self.ray_dir = self.ray_dir.normalize();
+            }
         } else {
-            self.simplex[0] = self.simplex[b_index];
-            self.simplex[1] = self.simplex[c_index];
+            self.ray_dir = self.ray;
         }
-    
-        self.simplex[2] = self.simplex[a_index];
-        self.simplex_len = 3;
-    
-        self.ray = -abc_dot_a0 / abc.length_squared() * abc;
-        if abc == DVec3::ZERO {
-            self.ray = abc;
-        }
-    
-        return false;
+        None
     }
-    
-    fn project_line_origen(&mut self) -> bool {
-        let a_index = 1;
-        let b_index = 0;
-    
-        let a = self.simplex[a_index].v;
-        let b = self.simplex[b_index].v;
-    
-        let ab = b - a;
-        let d = ab.dot(-a);
-    
-        if d == 0.0 {
-            /* Two extremely unlikely cases:
-                - AB is orthogonal to A: should never happen because it means the support
-                function did not do any progress and GJK should have stopped.
-                - A == origin
-            In any case, A is the closest to the origin */
-            self.origen_to_point(a_index, a);
-            return a == DVec3::ZERO;
+
+    /// Direction the next support point of `collider2` is searched in, `collider1` is searched in the opposite one.
+    pub(crate) fn search_direction(&self) -> DVec3 {
+        self.ray_dir
+    }
+
+    /// Adds the support point along the search direction to the simplex, returns the result once the query is finished.
+    pub(crate) fn step(&mut self, i: usize, support_point: Vertex) -> Option<GjkResult> {
+        self.support_point = support_point;
+        self.simplex.push(support_point);
+
+        self.omega = self.ray_dir.dot(support_point.v) / self.ray_dir.length();
+        if self.omega > self.config.upper_bound {
+            return Some(self.result(GjkStatus::Separated, self.omega - self.inflation, i + 1));
         }
-    
-        if d < 0.0 {
-            self.origen_to_point(a_index, a);
-        } else {
-            self.origen_to_segment(a_index, b_index, a, b, ab, d);
+
+        if self.use_nesterov_acceleration {
+            let frank_wolfe_duality_gap = 2.0 * self.ray.dot(self.ray - support_point.v);
+            if frank_wolfe_duality_gap - self.config.tolerance <= 0.0 {
+                self.use_nesterov_acceleration = false;
+                self.simplex.pop();
+                return None;
+            }
         }
-    
-        return false;
+
+        let cv_check_passed = self.check_convergence();
+        if i > 0 && cv_check_passed {
+            self.simplex.pop();
+
+            if self.use_nesterov_acceleration {
+                self.use_nesterov_acceleration = false;
+                return None;
+            }
+            let distance = self.ray_len - self.inflation;
+            return Some(self.result(separation_status(distance, self.config.tolerance), distance, i + 1));
+        }
+
+        let inside = self.simplex.project(self.config.sub_distance);
+        self.ray = self.simplex.ray;
+
+        if !inside {
+            self.ray_len = self.ray.length();
+        }
+
+        if inside || self.ray_len == 0.0 {
+            return Some(self.result(GjkStatus::Overlapping, -self.inflation, i + 1));
+        }
+        None
     }
-    
-    fn project_triangle_origen(&mut self) -> bool {
-        let a_index = 2;
-        let b_index = 1;
-        let c_index = 0;
-    
-        let a = self.simplex[a_index].v;
-        let b = self.simplex[b_index].v;
-        let c = self.simplex[c_index].v;
-    
-        let ab = b - a;
-        let ac = c - a;
-        let abc = ab.cross(ac);
-    
-        let edge_ac2o = abc.cross(ac).dot(-a);
-    
-        let t_b = |data: &mut Self| {
-            let towards_b = ab.dot(-a);
-            if towards_b < 0.0 {
-                data.origen_to_point(a_index, a);
-            } else {
-                data.origen_to_segment(a_index, b_index, a, b, ab, towards_b)
+
+    /// Result of a query that ran out of iterations, its distance is the best known upper bound.
+    pub(crate) fn unfinished(&self, max_iterations: usize) -> GjkResult {
+        self.result(GjkStatus::MaxIterationsReached, self.ray_len - self.inflation, max_iterations)
+    }
+
+    fn result(&self, status: GjkStatus, distance: f64, iterations: usize) -> GjkResult {
+        GjkResult {
+            status,
+            distance,
+            iterations,
+            ray: self.ray,
+            simplex_len: self.simplex.len,
+        }
+    }
+
+    /// Boolean intersection test that stops as soon as the answer is known, either because a support point
+    /// does not pass the origin and its direction separates the colliders, or because the simplex encloses the origin.
+    ///
+    /// Unlike `distance_nesterov_accelerated` no distance is computed. The returned `separating_axis` can be cached
+    /// and passed back as `ray_guess = Some(-axis)` for the next query of the same pair.
+    ///
+    /// The test is conservative: if it runs out of iterations before finding a separating axis,
+    /// the colliders are reported as intersecting.
+    pub fn intersects<A: SupportMap<F> + ?Sized, B: SupportMap<F> + ?Sized>(
+        &mut self,
+        collider1: &A,
+        collider2: &B,
+        max_iterations: usize,
+    ) -> Intersection {
+        self.start_query();
+
+        self.margin1 = collider1.margin().to_f64();
+        self.margin2 = collider2.margin().to_f64();
+        let inflation = self.margin1 + self.margin2 + self.config.inflation;
+
+        self.support_point = Self::support(collider1, collider2, self.ray);
+        self.simplex.set(self.support_point);
+        self.ray = self.support_point.v;
+        self.ray_len = self.ray.length();
+
+        for i in 0..max_iterations {
+            if self.ray_len <= inflation + self.config.tolerance {
+                return Intersection { intersects: true, separating_axis: DVec3::ZERO, iterations: i };
             }
-        };
-    
-        if edge_ac2o >= 0.0 {
-            let towards_c = ac.dot(-a);
-            if towards_c >= 0.0 {
-                self.origen_to_segment(a_index, c_index, a, c, ac, towards_c)
-            } else {
-                t_b(self);
+
+            self.support_point = Self::support(collider1, collider2, self.ray);
+
+            // All of collider1 - collider2 lies beyond the plane through the support point, further away than the margins.
+            self.omega = self.ray.dot(self.support_point.v) / self.ray_len;
+            if self.omega > inflation {
+                return Intersection {
+                    intersects: false,
+                    separating_axis: -self.ray / self.ray_len,
+                    iterations: i + 1,
+                };
             }
-        } else {
-            let edge_ab2o = ab.cross(abc).dot(-a);
-            if edge_ab2o >= 0.0 {
-                t_b(self);
-            } else {
-                return self.origen_to_triangle(a_index, b_index, c_index, abc, abc.dot(-a));
+
+            // No progress towards the origin, the core distance is known up to the tolerance.
+            if self.ray_len - self.omega <= self.config.tolerance * self.ray_len {
+                return Intersection { intersects: true, separating_axis: DVec3::ZERO, iterations: i + 1 };
+            }
+
+            self.simplex.push(self.support_point);
+            let inside = self.simplex.project(self.config.sub_distance);
+            self.ray = self.simplex.ray;
+            self.ray_len = self.ray.length();
+
+            if inside {
+                return Intersection { intersects: true, separating_axis: DVec3::ZERO, iterations: i + 1 };
             }
         }
-    
-        return false;
+
+        // No separating axis was found, err on the side of reporting a collision.
+        Intersection { intersects: true, separating_axis: DVec3::ZERO, iterations: max_iterations }
+    }
+
+    /// Witness points of the last query, computed from the barycentric coordinates
+    /// of the closest point on the final simplex and moved onto the margins of the shapes.
+    pub fn closest_points(&self) -> ClosestPoints {
+        let (point1, point2) = self.simplex.closest_points();
+        let normal = (-self.ray).normalize_or_zero();
+
+        ClosestPoints {
+            point1: point1 + normal * self.margin1,
+            point2: point2 - normal * self.margin2,
+            normal,
+        }
+    }
+
+    pub(crate) fn core_closest_points(&self) -> (DVec3, DVec3) {
+        self.simplex.closest_points()
+    }
+
+    pub(crate) fn margins(&self) -> (f64, f64) {
+        (self.margin1, self.margin2)
+    }
+
+    pub(crate) fn ray(&self) -> DVec3 {
+        self.ray
     }
     
-    fn project_tetra_to_origen(&mut self) -> bool {
-        let a_index = 3;
-        let b_index = 2;
-        let c_index = 1;
-        let d_index = 0;
-    
-        let a = self.simplex[a_index].v;
-        let b = self.simplex[b_index].v;
-        let c = self.simplex[c_index].v;
-        let d = self.simplex[d_index].v;
-    
-        let aa = a.length_squared();
-    
-        let da = d.dot(a);
-        let db = d.dot(b);
-        let dc = d.dot(c);
-        let dd = d.dot(d);
-        let da_aa = da - aa;
-    
-        let ca = c.dot(a);
-        let cb = c.dot(b);
-        let cc = c.dot(c);
-        let cd = dc;
-        let ca_aa = ca - aa;
-    
-        let ba = b.dot(a);
-        let bb = b.dot(b);
-        let bc = cb;
-        let bd = db;
-        let ba_aa = ba - aa;
-        let ba_ca = ba - ca;
-        let ca_da = ca - da;
-        let da_ba = da - ba;
-    
-        let a_cross_b = a.cross(b);
-        let a_cross_c = a.cross(c);
-    
-        let region_inside = |data: &mut Self| {
-            data.ray = DVec3::ZERO;
-            true
-        };
-    
-        let region_abc = |data: &mut Self| {
-            data.origen_to_triangle(
-                a_index,
-                b_index,
-                c_index,
-                (b - a).cross(c - a),
-                -c.dot(a_cross_b),
-            )
-        };
-    
-        let region_acd = |data: &mut Self| {
-            data.origen_to_triangle(
-                a_index,
-                c_index,
-                d_index,
-                (c - a).cross(d - a),
-                -d.dot(a_cross_c),
-            )
-        };
-    
-        let region_adb = |data: &mut Self| {
-            data.origen_to_triangle(
-                a_index,
-                d_index,
-                b_index,
-                (d - a).cross(b - a),
-                d.dot(a_cross_b),
-            )
-        };
-    
-        let region_ab = |data: &mut Self| {
-            data.origen_to_segment(a_index, b_index, a, b, b - a, -ba_aa)
-        };
+    pub(crate) fn simplex(&self) -> &[Vertex] {
+        self.simplex.vertices()
+    }
     
-        let region_ac = |data: &mut Self| {
-            data.origen_to_segment(a_index, c_index, a, c, c - a, -ca_aa)
+    /// Support point of the Minkowski difference of the core shapes along `-dir`, widened to `f64`.
+    fn support<A: SupportMap<F> + ?Sized, B: SupportMap<F> + ?Sized>(collider1: &A, collider2: &B, dir: DVec3) -> Vertex {
+        let search_dir = F::Vec3::from_dvec3(dir);
+        let (s0, index0) = collider1.core_support_vertex(-search_dir);
+        let (s1, index1) = collider2.core_support_vertex(search_dir);
+
+        Vertex { dir, index0, index1, ..Vertex::new(s0.as_dvec3(), s1.as_dvec3()) }
+    }
+
+    /// The vertex of a previous query on the colliders in their current pose, see `GjkCache`.
+    fn cached_support<A: SupportMap<F> + ?Sized, B: SupportMap<F> + ?Sized>(
+        collider1: &A,
+        collider2: &B,
+        cached: &CachedVertex,
+    ) -> Vertex {
+        let search_dir = F::Vec3::from_dvec3(cached.dir);
+        let (s0, index0) = match cached.index1.and_then(|index| collider1.core_vertex(index)) {
+            Some(point) => (point, cached.index1),
+            None => collider1.core_support_vertex(-search_dir),
         };
-    
-        let region_ad = |data: &mut Self| {
-            data.origen_to_segment(a_index, d_index, a, d, d - a, -da_aa)
+        let (s1, index1) = match cached.index2.and_then(|index| collider2.core_vertex(index)) {
+            Some(point) => (point, cached.index2),
+            None => collider2.core_support_vertex(search_dir),
         };
+
+        Vertex { dir: cached.dir, index0, index1, ..Vertex::new(s0.as_dvec3(), s1.as_dvec3()) }
+    }
+
+    fn check_convergence(&mut self) -> bool {
+        self.alpha = self.alpha.max(self.omega);
     
-        let region_a = |data: &mut Self| data.origen_to_point(a_index, a);
-    
-        if ba_aa <= 0.0 {
-            if -d.dot(a_cross_b) <= 0.0 {
-                if ba * da_ba + bd * ba_aa - bb * da_aa <= 0.0 {
-                    if da_aa <= 0.0 {
-                        if ba * ba_ca + bb * ca_aa - bc * ba_aa <= 0.0 {
-                            region_abc(self);
-                        } else {
-                            region_ab(self);
-                        }
-                    } else {
-                        if ba * ba_ca + bb * ca_aa - bc * ba_aa <= 0.0 {
-                            if ca * ba_ca + cb * ca_aa - cc * ba_aa <= 0.0 {
-                                if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
-                                    region_acd(self);
-                                } else {
-                                    region_ac(self);
-                                }
-                            } else {
-                                region_abc(self);
-                            }
-                        } else {
-                            region_ab(self);
-                        }
-                    }
-                } else {
-                    if da * da_ba + dd * ba_aa - db * da_aa <= 0.0 {
-                        region_adb(self);
-                    } else {
-                        if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
-                            if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
-                                region_ad(self);
-                            } else {
-                                region_acd(self);
-                            }
-                        } else {
-                            if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
-                                region_ad(self);
-                            } else {
-                                region_ac(self);
-                            }
-                        }
-                    }
-                }
-            } else {
-                if c.dot(a_cross_b) <= 0.0 {
-                    if ba * ba_ca + bb * ca_aa - bc * ba_aa <= 0.0 {
-                        if ca * ba_ca + cb * ca_aa - cc * ba_aa <= 0.0 {
-                            if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
-                                region_acd(self);
-                            } else {
-                                region_ac(self);
-                            }
-                        } else {
-                            region_abc(self);
-                        }
-                    } else {
-                        region_ad(self);
-                    }
-                } else {
-                    if d.dot(a_cross_c) <= 0.0 {
-                        if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
-                            if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
-                                region_ad(self);
-                            } else {
-                                region_acd(self);
-                            }
-                        } else {
-                            if ca_aa <= 0.0 {
-                                region_ac(self);
-                            } else {
-                                region_ad(self);
-                            }
-                        }
-                    } else {
-                        return region_inside(self);
-                    }
-                }
-            }
-        } else {
-            if ca_aa <= 0.0 {
-                if d.dot(a_cross_c) <= 0.0 {
-                    if da_aa <= 0.0 {
-                        if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
-                            if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
-                                if da * da_ba + dd * ba_aa - db * da_aa <= 0.0 {
-                                    region_adb(self);
-                                } else {
-                                    region_ad(self);
-                                }
-                            } else {
-                                region_acd(self);
-                            }
-                        } else {
-                            if ca * ba_ca + cb * ca_aa - cc * ba_aa <= 0.0 {
-                                region_ac(self);
-                            } else {
-                                region_abc(self);
-                            }
-                        }
-                    } else {
-                        if ca * ba_ca + cb * ca_aa - cc * ba_aa <= 0.0 {
-                            if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
-                                region_acd(self);
-                            } else {
-                                region_ac(self);
-                            }
-                        } else {
-                            if c.dot(a_cross_b) <= 0.0 {
-                                region_abc(self);
-                            } else {
-                                region_acd(self);
-                            }
-                        }
-                    }
-                } else {
-                    if c.dot(a_cross_b) <= 0.0 {
-                        if ca * ba_ca + cb * ca_aa - cc * ba_aa <= 0.0 {
-                            region_ac(self);
-                        } else {
-                            region_abc(self);
-                        }
-                    } else {
-                        if -d.dot(a_cross_b) <= 0.0 {
-                            if da * da_ba + dd * ba_aa - db * da_aa <= 0.0 {
-                                region_adb(self);
-                            } else {
-                                region_ad(self);
-                            }
-                        } else {
-                            return region_inside(self);
-                        }
-                    }
-                }
-            } else {
-                if da_aa <= 0.0 {
-                    if -d.dot(a_cross_b) <= 0.0 {
-                        if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
-                            if da * da_ba + dd * ba_aa - db * da_aa <= 0.0 {
-                                region_adb(self);
-                            } else {
-                                region_ad(self);
-                            }
-                        } else {
-                            if d.dot(a_cross_c) <= 0.0 {
-                                region_acd(self);
-                            } else {
-                                region_adb(self);
-                            }
-                        }
-                    } else {
-                        if d.dot(a_cross_c) <= 0.0 {
-                            if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
-                                region_ad(self);
-                            } else {
-                                region_acd(self);
-                            }
-                        } else {
-                            return region_inside(self);
-                        }
-                    }
-                } else {
-                    region_a(self);
+        self.config.convergence_criterion.is_converged(self.ray_len, self.alpha, self.config.tolerance)
+    }
+}
+
+impl DistanceSolver for GJKNesterov {
+    fn distance<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(
+        &mut self,
+        collider1: &A,
+        collider2: &B,
+        max_iterations: usize,
+    ) -> GjkResult {
+        self.distance_nesterov_accelerated(collider1, collider2, max_iterations)
+    }
+
+    fn closest_points(&self) -> ClosestPoints {
+        GJKNesterov::closest_points(self)
+    }
+}
+
+/// Status of a converged query with the given distance.
+pub(crate) fn separation_status(distance: f64, tolerance: f64) -> GjkStatus {
+    if distance < -tolerance {
+        GjkStatus::Overlapping
+    } else if distance < tolerance {
+        GjkStatus::Touching
+    } else {
+        GjkStatus::Separated
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::{dvec3, DMat4, DVec3};
+
+    use crate::{colliders::Collider, gjk::{ConvergenceCriterion, GjkCache, GjkConfig, GJKNesterov, GjkStatus}, gjk_classic::GJKClassic};
+
+    #[test]
+    fn test_closest_points_spheres() {
+        let collider1 = Collider::new_sphere(DMat4::from_translation(dvec3(0.0, 0.0, 0.0)), 1.0);
+        let collider2 = Collider::new_sphere(DMat4::from_translation(dvec3(4.0, 0.0, 0.0)), 1.0);
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let result = gjk.distance_nesterov_accelerated(&collider1, &collider2, 100);
+        let points = gjk.closest_points();
+
+        assert!(result.status == GjkStatus::Separated);
+        assert!((result.distance - 2.0).abs() < 1e-3);
+        assert!(points.point1.distance(dvec3(1.0, 0.0, 0.0)) < 1e-2);
+        assert!(points.point2.distance(dvec3(3.0, 0.0, 0.0)) < 1e-2);
+        assert!(points.normal.distance(dvec3(1.0, 0.0, 0.0)) < 1e-2);
+    }
+
+    #[test]
+    fn test_closest_points_boxes() {
+        let collider1 = Collider::new_box(DMat4::IDENTITY, dvec3(2.0, 2.0, 2.0));
+        let collider2 = Collider::new_box(DMat4::from_translation(dvec3(0.5, 3.5, 0.25)), dvec3(1.0, 1.0, 1.0));
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let result = gjk.distance_nesterov_accelerated(&collider1, &collider2, 100);
+        let points = gjk.closest_points();
+
+        assert!(result.status == GjkStatus::Separated);
+        assert!((result.distance - 2.0).abs() < 1e-3);
+        assert!((points.point1.y - 1.0).abs() < 1e-3);
+        assert!((points.point2.y - 3.0).abs() < 1e-3);
+        assert!((points.point1.distance(points.point2) - result.distance).abs() < 1e-3);
+        assert!(points.normal.distance(dvec3(0.0, 1.0, 0.0)) < 1e-3);
+    }
+
+    #[test]
+    fn test_max_iterations_reached() {
+        let collider1 = Collider::new_sphere(DMat4::IDENTITY, 1.0);
+        let collider2 = Collider::new_sphere(DMat4::from_translation(dvec3(3.0, 4.0, 0.0)), 1.0);
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let result = gjk.distance_nesterov_accelerated(&collider1, &collider2, 1);
+
+        assert!(result.status == GjkStatus::MaxIterationsReached);
+        assert!(result.iterations == 1);
+        assert!(result.distance >= 3.0);
+    }
+
+    #[test]
+    fn test_intersects() {
+        let collider1 = Collider::new_box(DMat4::IDENTITY, dvec3(2.0, 2.0, 2.0));
+        let collider2 = Collider::new_sphere(DMat4::from_translation(dvec3(0.0, 0.0, 2.5)), 1.0);
+        let collider3 = Collider::new_sphere(DMat4::from_translation(dvec3(0.0, 0.0, 1.5)), 1.0);
+
+        let result = GJKNesterov::new(None, 1e-6).intersects(&collider1, &collider2, 100);
+        assert!(!result.intersects);
+        assert!(result.separating_axis.dot(dvec3(0.0, 0.0, 1.0)) > 0.0);
+
+        let result = GJKNesterov::new(None, 1e-6).intersects(&collider1, &collider3, 100);
+        assert!(result.intersects);
+        assert!(result.separating_axis == DVec3::ZERO);
+
+        // Without a separating axis the answer is conservative.
+        let result = GJKNesterov::new(None, 1e-6).intersects(&collider1, &collider2, 0);
+        assert!(result.intersects);
+        assert!(result.separating_axis == DVec3::ZERO);
+    }
+
+    #[test]
+    fn test_reuse_for_many_pairs() {
+        let colliders = [
+            Collider::new_sphere(DMat4::IDENTITY, 1.0),
+            Collider::new_box(DMat4::from_translation(dvec3(0.5, 3.5, 0.25)), dvec3(1.0, 1.0, 1.0)),
+            Collider::new_capluse(DMat4::from_translation(dvec3(-3.0, 0.0, 0.0)), 0.5, 2.0),
+            Collider::new_cylinder(DMat4::from_translation(dvec3(0.0, 0.5, 0.5)), 1.0, 2.0),
+        ];
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        for _ in 0..2 {
+            for collider1 in colliders.iter() {
+                for collider2 in colliders.iter() {
+                    let reused = gjk.distance_nesterov_accelerated(collider1, collider2, 100);
+                    let fresh = GJKNesterov::new(None, 1e-6).distance_nesterov_accelerated(collider1, collider2, 100);
+
+                    assert!(reused.status == fresh.status);
+                    assert!(reused.iterations == fresh.iterations);
+                    assert!(reused.distance == fresh.distance);
                 }
             }
         }
-    
-        return false;
     }
-}
 
-impl Vertex {
-    fn new(s0: DVec3, s1: DVec3) -> Self {
-        Self { v: s0 - s1, s0, s1 }
+    #[test]
+    fn test_ray_guess_applies_to_one_query() {
+        let collider1 = Collider::new_sphere(DMat4::IDENTITY, 1.0);
+        let collider2 = Collider::new_box(DMat4::from_translation(dvec3(0.5, 3.5, 0.25)), dvec3(1.0, 1.0, 1.0));
+        let collider3 = Collider::new_cone(DMat4::from_translation(dvec3(0.5, 2.5, 3.0)), 1.0, 2.0);
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        gjk.warm_start(&GjkCache { ray: dvec3(0.0, -3.0, 0.0), ..Default::default() });
+        gjk.distance_nesterov_accelerated(&collider1, &collider2, 100);
+
+        // The guess of the first pair does not leak into the query of another pair.
+        let reused = gjk.distance_nesterov_accelerated(&collider1, &collider3, 100);
+        let fresh = GJKNesterov::new(None, 1e-6).distance_nesterov_accelerated(&collider1, &collider3, 100);
+        assert!(reused.iterations == fresh.iterations);
+        assert!(reused.ray == fresh.ray);
+
+        let mut classic = GJKClassic::new(Some(dvec3(0.0, -3.0, 0.0)), 1e-6);
+        classic.distance(&collider1, &collider2, 100);
+        let reused = classic.distance(&collider1, &collider3, 100);
+        let fresh = GJKClassic::new(None, 1e-6).distance(&collider1, &collider3, 100);
+        assert!(reused.iterations == fresh.iterations);
+        assert!(reused.ray == fresh.ray);
     }
-}
 
-impl Default for Vertex {
-    fn default() -> Self {
-        Self::new(DVec3::ZERO, DVec3::ZERO)
+    #[test]
+    fn test_warm_start_looks_up_moved_vertices() {
+        let vertices = [
+            dvec3(1.0, 0.0, 0.0), dvec3(-1.0, 0.0, 0.0), dvec3(0.0, 1.0, 0.0),
+            dvec3(0.0, -1.0, 0.0), dvec3(0.0, 0.0, 1.0), dvec3(0.0, 0.0, -1.0),
+        ];
+        let collider2 = Collider::new_box(DMat4::from_translation(dvec3(0.0, 3.0, 0.5)), dvec3(1.0, 1.0, 1.0));
+
+        let mut gjk = GJKNesterov::new(None, 1e-6);
+        gjk.distance_nesterov_accelerated(&Collider::new_convex_hull(DMat4::IDENTITY, &vertices), &collider2, 100);
+        let cache = gjk.cache();
+        assert!(cache.len > 0);
+        assert!(cache.vertices[..cache.len].iter().all(|vertex| vertex.index1.is_some()));
+
+        // The stale cached points would put the rebuilt simplex outside the moved Minkowski difference.
+        let moved = Collider::new_convex_hull(DMat4::from_translation(dvec3(0.1, 0.2, 0.0)), &vertices);
+        let warm = GJKNesterov::with_cache(&cache, 1e-6).distance_nesterov_accelerated(&moved, &collider2, 100);
+        let cold = GJKNesterov::new(None, 1e-6).distance_nesterov_accelerated(&moved, &collider2, 100);
+        assert!(warm.status == cold.status);
+        assert!((warm.distance - cold.distance).abs() < 1e-6);
+        assert!(warm.iterations <= cold.iterations);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_config_variants_agree() {
+        let collider1 = Collider::new_cylinder(DMat4::IDENTITY, 1.0, 2.0);
+        let collider2 = Collider::new_box(DMat4::from_translation(dvec3(2.0, 2.5, 0.5)), dvec3(1.0, 1.0, 1.0));
+        let expected = GJKNesterov::new(None, 1e-9).distance_nesterov_accelerated(&collider1, &collider2, 1000).distance;
+
+        for use_nesterov_acceleration in [true, false] {
+            for normalize_support_direction in [true, false] {
+                for convergence_criterion in [ConvergenceCriterion::Relative, ConvergenceCriterion::Absolute] {
+                    let config = GjkConfig {
+                        use_nesterov_acceleration,
+                        normalize_support_direction,
+                        convergence_criterion,
+                        inflation: 0.25,
+                        ..Default::default()
+                    };
+                    let result = GJKNesterov::with_config(None, config).distance_nesterov_accelerated(&collider1, &collider2, 100);
+
+                    assert!(result.status == GjkStatus::Separated);
+                    assert!((result.distance - (expected - 0.25)).abs() < 1e-4);
+                }
+            }
+        }
+
+        let config = GjkConfig { upper_bound: 0.1, ..Default::default() };
+        let result = GJKNesterov::with_config(None, config).distance_nesterov_accelerated(&collider1, &collider2, 100);
+        assert!(result.status == GjkStatus::Separated);
+        assert!(result.distance > 0.1);
+    }
+}
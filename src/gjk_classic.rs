@@ -0,0 +1,142 @@
+use glam::DVec3;
+
+use crate::{
+    gjk::{ClosestPoints, ConvergenceCriterion, DistanceSolver, GjkConfig, GjkResult, GJKNesterov, SubDistance},
+    support_map::SupportMap,
+};
+
+/// Options of `GJKClassic`, those of `GjkConfig` without the acceleration.
+#[derive(Clone, Copy, Debug)]
+pub struct ClassicConfig {
+    pub tolerance: f64,
+    /// The query stops as soon as the lower bound of the distance exceeds this.
+    pub upper_bound: f64,
+    /// Radius the colliders are inflated by in total on top of their margins.
+    pub inflation: f64,
+    pub convergence_criterion: ConvergenceCriterion,
+    pub sub_distance: SubDistance,
+}
+
+impl Default for ClassicConfig {
+    fn default() -> Self {
+        let config = GjkConfig::default();
+
+        Self {
+            tolerance: config.tolerance,
+            upper_bound: config.upper_bound,
+            inflation: config.inflation,
+            convergence_criterion: config.convergence_criterion,
+            sub_distance: config.sub_distance,
+        }
+    }
+}
+
+impl From<ClassicConfig> for GjkConfig {
+    fn from(config: ClassicConfig) -> Self {
+        Self {
+            tolerance: config.tolerance,
+            use_nesterov_acceleration: false,
+            normalize_support_direction: false,
+            upper_bound: config.upper_bound,
+            inflation: config.inflation,
+            convergence_criterion: config.convergence_criterion,
+            sub_distance: config.sub_distance,
+        }
+    }
+}
+
+/// Original GJK of Gilbert, Johnson and Keerthi that always searches along the current closest point,
+/// as reference for `GJKNesterov`. It runs the loop of `GJKNesterov` without acceleration.
+pub struct GJKClassic
+{
+    gjk: GJKNesterov,
+}
+
+impl GJKClassic
+{
+    pub fn new(ray_guess: Option<DVec3>, tolerance: f64) -> Self {
+        Self::with_config(ray_guess, ClassicConfig { tolerance, ..Default::default() })
+    }
+
+    /// The ray guess only applies to the first query, later queries start from the x axis.
+    pub fn with_config(ray_guess: Option<DVec3>, config: ClassicConfig) -> Self {
+        Self {
+            gjk: GJKNesterov::with_config(ray_guess, config.into()),
+        }
+    }
+
+    pub fn distance<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(
+        &mut self,
+        collider1: &A,
+        collider2: &B,
+        max_iterations: usize,
+    ) -> GjkResult {
+        self.gjk.distance_nesterov_accelerated(collider1, collider2, max_iterations)
+    }
+
+    /// Witness points of the last query on the shapes including their margins.
+    pub fn closest_points(&self) -> ClosestPoints {
+        self.gjk.closest_points()
+    }
+}
+
+impl DistanceSolver for GJKClassic {
+    fn distance<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(
+        &mut self,
+        collider1: &A,
+        collider2: &B,
+        max_iterations: usize,
+    ) -> GjkResult {
+        GJKClassic::distance(self, collider1, collider2, max_iterations)
+    }
+
+    fn closest_points(&self) -> ClosestPoints {
+        GJKClassic::closest_points(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::{
+        colliders::Collider,
+        gjk::{DistanceSolver, GjkConfig, GJKNesterov, GjkStatus, SubDistance},
+        gjk_classic::{ClassicConfig, GJKClassic},
+    };
+
+    fn run<S: DistanceSolver>(solver: &mut S, collider1: &Collider, collider2: &Collider) -> f64 {
+        let result = solver.distance(collider1, collider2, 1000);
+        assert!(result.status != GjkStatus::MaxIterationsReached);
+
+        if result.status == GjkStatus::Separated {
+            let points = solver.closest_points();
+            assert!((points.point1.distance(points.point2) - result.distance).abs() < 1e-4);
+        }
+        result.distance.max(0.0)
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let johnson = GjkConfig { tolerance: 1e-8, ..Default::default() };
+        let signed_volumes = GjkConfig { sub_distance: SubDistance::SignedVolumes, ..johnson };
+        let classic_johnson = ClassicConfig { tolerance: 1e-8, ..Default::default() };
+        let classic_signed_volumes = ClassicConfig { sub_distance: SubDistance::SignedVolumes, ..classic_johnson };
+
+        for _ in 0..500 {
+            let collider1 = Collider::new_random_any(&mut rng, 0.5..3.0);
+            let collider2 = Collider::new_random_any(&mut rng, 0.5..3.0);
+
+            let expected = run(&mut GJKNesterov::with_config(None, johnson), &collider1, &collider2);
+            for distance in [
+                run(&mut GJKNesterov::with_config(None, signed_volumes), &collider1, &collider2),
+                run(&mut GJKClassic::with_config(None, classic_johnson), &collider1, &collider2),
+                run(&mut GJKClassic::with_config(None, classic_signed_volumes), &collider1, &collider2),
+            ] {
+                assert!((distance - expected).abs() < 1e-4);
+            }
+        }
+    }
+}
@@ -1,96 +1,392 @@
-use std::fs;
+use std::{error::Error, fmt, fs, io};
 
-use glam::{dvec3, DMat4, dmat4, dvec4, DVec3, DVec4};
+use glam::{dvec3, DMat3, DMat4, dmat4, dvec4, DQuat, DVec3, DVec4, EulerRot};
 use serde_json::Value;
 
 use crate::{colliders::Collider};
 
-pub fn load_test_file(path: &str) -> Vec<(Collider, Collider, f64)> {
-    let contents = fs::read_to_string(path).unwrap();
-    let json_data: Value = serde_json::from_str(&contents).unwrap();
+/// One entry of a test file, two colliders and their reference distance.
+///
+/// With the `serde` feature test files can also be read and written with any serde format as `Vec<TestCase>`.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TestCase {
+    pub collider1: Collider,
+    pub collider2: Collider,
+    pub distance: f64,
+}
 
-    let mut result: Vec<(Collider, Collider, f64)> = Vec::new();
+/// What is wrong with a value of a test file.
+#[derive(Debug)]
+pub enum LoadErrorKind {
+    Io(io::Error),
+    Json(serde_json::Error),
+    MissingField,
+    WrongType { expected: &'static str },
+    UnknownColliderType(String),
+    NonFinite,
+    SingularMatrix,
+    /// The rotation part of a pose is not orthonormal with determinant 1, or a quaternion is not normalized.
+    ImproperRotation,
+    /// The bottom row of a `collider2origin` matrix is not `[0, 0, 0, 1]`.
+    NotAffine,
+    /// The field conflicts with another pose field, e.g. `euler` next to `rotation`.
+    ConflictingField,
+    /// A radius, height or size is negative.
+    Negative,
+    /// A convex hull without vertices.
+    Empty,
+}
 
-    for json_obj in json_data.as_array().unwrap() {
+/// Error of loading a test file, with the location of the offending value.
+#[derive(Debug)]
+pub struct LoadError {
+    /// Index of the test case in the file, `None` for errors of the whole file or of a single collider.
+    pub entry: Option<usize>,
+    /// Path of the value inside its entry like `collider1.collider2origin[2][3]`, empty for the whole entry.
+    pub path: String,
+    pub kind: LoadErrorKind,
+}
 
-        let collider1 = parse_collider(&json_obj["collider1"]);
-        let collider2 = parse_collider(&json_obj["collider2"]);
-        let distance = json_obj["distance"].as_f64().unwrap();
+impl LoadError {
+    fn new(path: &str, kind: LoadErrorKind) -> Self {
+        Self { entry: None, path: path.to_owned(), kind }
+    }
+
+    fn wrong_type(path: &str, expected: &'static str) -> Self {
+        Self::new(path, LoadErrorKind::WrongType { expected })
+    }
+}
 
-        result.push((collider1, collider2, distance))
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(entry) = self.entry {
+            write!(f, "entry {entry}: ")?;
+        }
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{}", self.kind)
     }
+}
 
-    result
+impl fmt::Display for LoadErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadErrorKind::Io(error) => write!(f, "{error}"),
+            LoadErrorKind::Json(error) => write!(f, "invalid JSON: {error}"),
+            LoadErrorKind::MissingField => write!(f, "missing field"),
+            LoadErrorKind::WrongType { expected } => write!(f, "expected {expected}"),
+            LoadErrorKind::UnknownColliderType(typ) => write!(f, "unknown collider type {typ:?}"),
+            LoadErrorKind::NonFinite => write!(f, "non-finite value"),
+            LoadErrorKind::SingularMatrix => write!(f, "singular matrix"),
+            LoadErrorKind::ImproperRotation => write!(f, "not a proper rotation"),
+            LoadErrorKind::NotAffine => write!(f, "bottom row is not [0, 0, 0, 1]"),
+            LoadErrorKind::ConflictingField => write!(f, "conflicts with another pose field"),
+            LoadErrorKind::Negative => write!(f, "negative value"),
+            LoadErrorKind::Empty => write!(f, "empty array"),
+        }
+    }
 }
 
-pub fn parse_collider(json_obj: &Value) -> Collider {
-    match json_obj["type"].as_str().unwrap() {
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            LoadErrorKind::Io(error) => Some(error),
+            LoadErrorKind::Json(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+pub fn load_test_file(path: &str) -> Result<Vec<(Collider, Collider, f64)>, LoadError> {
+    let contents = fs::read_to_string(path).map_err(|error| LoadError::new("", LoadErrorKind::Io(error)))?;
+    let json_data: Value = serde_json::from_str(&contents).map_err(|error| LoadError::new("", LoadErrorKind::Json(error)))?;
+
+    parse_test_cases(&json_data)
+}
+
+fn parse_test_cases(json_data: &Value) -> Result<Vec<(Collider, Collider, f64)>, LoadError> {
+    let entries = json_data.as_array().ok_or_else(|| LoadError::wrong_type("", "array of test cases"))?;
+
+    let mut result: Vec<(Collider, Collider, f64)> = Vec::new();
+
+    for (i, json_obj) in entries.iter().enumerate() {
+        let parse_entry = || -> Result<(Collider, Collider, f64), LoadError> {
+            let collider1 = parse_collider_at(field(json_obj, "", "collider1")?, "collider1")?;
+            let collider2 = parse_collider_at(field(json_obj, "", "collider2")?, "collider2")?;
+            let distance = parse_f64(field(json_obj, "", "distance")?, "distance")?;
+            Ok((collider1, collider2, distance))
+        };
+
+        result.push(parse_entry().map_err(|error| LoadError { entry: Some(i), ..error })?);
+    }
+
+    Ok(result)
+}
+
+/// Writes test cases in the format read by `load_test_file`, one entry per line.
+/// The colliders are written with their serde representation.
+#[cfg(feature = "serde")]
+pub fn write_test_file(path: &str, test_data: &[(Collider, Collider, f64)]) -> io::Result<()> {
+    let entries: Vec<String> = test_data.iter()
+        .map(|(collider1, collider2, distance)| {
+            serde_json::json!({
+                "collider1": collider1,
+                "collider2": collider2,
+                "distance": distance,
+            }).to_string()
+        })
+        .collect();
+
+    fs::write(path, format!("[\n{}\n]\n", entries.join(",\n")))
+}
+
+pub fn parse_collider(json_obj: &Value) -> Result<Collider, LoadError> {
+    parse_collider_at(json_obj, "")
+}
+
+fn parse_collider_at(json_obj: &Value, path: &str) -> Result<Collider, LoadError> {
+    let typ = field(json_obj, path, "type")?;
+    let typ = typ.as_str().ok_or_else(|| LoadError::wrong_type(&join(path, "type"), "string"))?;
+
+    let number = |key: &str| parse_dimension(field(json_obj, path, key)?, &join(path, key));
+    let vec3 = |key: &str| parse_size(field(json_obj, path, key)?, &join(path, key));
+
+    match typ {
         "Sphere" => {
-            let collider2origin = parse_mat4(&json_obj["collider2origin"]);
+            let collider2origin = parse_pose(json_obj, path)?;
 
-            let radius = json_obj["radius"].as_f64().unwrap();
+            let radius = number("radius")?;
 
-            Collider::new_sphere(collider2origin, radius)
+            Ok(Collider::new_sphere(collider2origin, radius))
         }
         "Capsule" => {
-            let collider2origin = parse_mat4(&json_obj["collider2origin"]);
+            let collider2origin = parse_pose(json_obj, path)?;
 
-            let radius = json_obj["radius"].as_f64().unwrap();
-            let height = json_obj["height"].as_f64().unwrap();
+            let radius = number("radius")?;
+            let height = number("height")?;
 
-            Collider::new_capluse(collider2origin, radius, height)
+            Ok(Collider::new_capluse(collider2origin, radius, height))
         }   
         "Cylinder" => {
-            let collider2origin = parse_mat4(&json_obj["collider2origin"]);
+            let collider2origin = parse_pose(json_obj, path)?;
 
-            let radius = json_obj["radius"].as_f64().unwrap();
-            let height = json_obj["height"].as_f64().unwrap();
+            let radius = number("radius")?;
+            let height = number("height")?;
 
-            Collider::new_cylinder(collider2origin, radius, height)
+            Ok(Collider::new_cylinder(collider2origin, radius, height))
         }
         "Box" => {
-            let collider2origin = parse_mat4(&json_obj["collider2origin"]);
+            let collider2origin = parse_pose(json_obj, path)?;
 
-            let size = parse_vec3(&json_obj["size"]);
+            let size = vec3("size")?;
             
-            Collider::new_box(collider2origin, size)
+            Ok(Collider::new_box(collider2origin, size))
+        }
+        "Cone" => {
+            let collider2origin = parse_pose(json_obj, path)?;
+
+            let radius = number("radius")?;
+            let height = number("height")?;
+
+            Ok(Collider::new_cone(collider2origin, radius, height))
+        }
+        "Ellipsoid" => {
+            let collider2origin = parse_pose(json_obj, path)?;
+
+            let size = vec3("size")?;
+
+            Ok(Collider::new_ellipsoid(collider2origin, size))
+        }
+        "RoundedBox" => {
+            let collider2origin = parse_pose(json_obj, path)?;
+
+            let size = vec3("size")?;
+            let radius = number("radius")?;
+
+            Ok(Collider::new_rounded_box(collider2origin, size, radius))
+        }
+        "ConvexHull" => {
+            let collider2origin = parse_pose(json_obj, path)?;
+
+            let vertices_path = join(path, "vertices");
+            let vertices: Vec<DVec3> = field(json_obj, path, "vertices")?.as_array()
+                .ok_or_else(|| LoadError::wrong_type(&vertices_path, "array of vertices"))?
+                .iter()
+                .enumerate()
+                .map(|(i, vertex)| parse_vec3(vertex, &format!("{vertices_path}[{i}]")))
+                .collect::<Result<_, _>>()?;
+            if vertices.is_empty() {
+                return Err(LoadError::new(&vertices_path, LoadErrorKind::Empty));
+            }
+
+            Ok(Collider::new_convex_hull(collider2origin, &vertices))
         }
-        &_ => todo!()
+        typ => Err(LoadError::new(&join(path, "type"), LoadErrorKind::UnknownColliderType(typ.to_owned()))),
     }
 }
 
-fn parse_vec3(json_obj: &Value) -> DVec3 {
-    dvec3(
-        json_obj[0].as_f64().unwrap(),
-        json_obj[1].as_f64().unwrap(),
-        json_obj[2].as_f64().unwrap(),
-    )
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() { key.to_owned() } else { format!("{path}.{key}") }
 }
 
-fn parse_vec4(json_obj: &Value) -> DVec4 {
-    dvec4(
-        json_obj[0].as_f64().unwrap(),
-        json_obj[1].as_f64().unwrap(),
-        json_obj[2].as_f64().unwrap(),
-        json_obj[3].as_f64().unwrap(),
-    )
+fn field<'a>(json_obj: &'a Value, path: &str, key: &str) -> Result<&'a Value, LoadError> {
+    json_obj.get(key).ok_or_else(|| LoadError::new(&join(path, key), LoadErrorKind::MissingField))
 }
 
-fn parse_mat4(json_obj: &Value) -> DMat4 {
-    dmat4(
-        parse_vec4(&json_obj[0]),
-        parse_vec4(&json_obj[1]),
-        parse_vec4(&json_obj[2]),
-        parse_vec4(&json_obj[3]),
-    ).transpose()
+fn parse_f64(json_obj: &Value, path: &str) -> Result<f64, LoadError> {
+    if !json_obj.is_number() {
+        return Err(LoadError::wrong_type(path, "number"));
+    }
+
+    // Numbers out of the range of f64 are kept as text and do not convert.
+    match json_obj.as_f64() {
+        Some(value) if value.is_finite() => Ok(value),
+        _ => Err(LoadError::new(path, LoadErrorKind::NonFinite)),
+    }
+}
+
+fn parse_dimension(json_obj: &Value, path: &str) -> Result<f64, LoadError> {
+    let value = parse_f64(json_obj, path)?;
+    if value < 0.0 {
+        return Err(LoadError::new(path, LoadErrorKind::Negative));
+    }
+    Ok(value)
+}
+
+fn parse_size(json_obj: &Value, path: &str) -> Result<DVec3, LoadError> {
+    let size = parse_vec3(json_obj, path)?;
+    match size.to_array().iter().position(|value| *value < 0.0) {
+        Some(i) => Err(LoadError::new(&format!("{path}[{i}]"), LoadErrorKind::Negative)),
+        None => Ok(size),
+    }
+}
+
+fn parse_array<const N: usize>(json_obj: &Value, path: &str, expected: &'static str) -> Result<[f64; N], LoadError> {
+    let array = json_obj.as_array()
+        .filter(|array| array.len() == N)
+        .ok_or_else(|| LoadError::wrong_type(path, expected))?;
+
+    let mut values = [0.0; N];
+    for (i, value) in array.iter().enumerate() {
+        values[i] = parse_f64(value, &format!("{path}[{i}]"))?;
+    }
+    Ok(values)
+}
+
+fn parse_vec3(json_obj: &Value, path: &str) -> Result<DVec3, LoadError> {
+    let [x, y, z] = parse_array(json_obj, path, "array of 3 numbers")?;
+    Ok(dvec3(x, y, z))
+}
+
+fn parse_vec4(json_obj: &Value, path: &str) -> Result<DVec4, LoadError> {
+    let [x, y, z, w] = parse_array(json_obj, path, "array of 4 numbers")?;
+    Ok(dvec4(x, y, z, w))
+}
+
+fn parse_mat4(json_obj: &Value, path: &str) -> Result<DMat4, LoadError> {
+    let rows = json_obj.as_array()
+        .filter(|rows| rows.len() == 4)
+        .ok_or_else(|| LoadError::wrong_type(path, "array of 4 rows"))?;
+
+    Ok(dmat4(
+        parse_vec4(&rows[0], &format!("{path}[0]"))?,
+        parse_vec4(&rows[1], &format!("{path}[1]"))?,
+        parse_vec4(&rows[2], &format!("{path}[2]"))?,
+        parse_vec4(&rows[3], &format!("{path}[3]"))?,
+    ).transpose())
+}
+
+fn parse_pose(json_obj: &Value, path: &str) -> Result<DMat4, LoadError> {
+    let pose = PoseFields {
+        collider2origin: json_obj.get("collider2origin").map(|value| parse_mat4(value, &join(path, "collider2origin"))).transpose()?,
+        center: json_obj.get("center").map(|value| parse_vec3(value, &join(path, "center"))).transpose()?,
+        rotation: json_obj.get("rotation").map(|value| parse_vec4(value, &join(path, "rotation")).map(DQuat::from_vec4)).transpose()?,
+        euler: json_obj.get("euler").map(|value| parse_vec3(value, &join(path, "euler"))).transpose()?,
+    };
+
+    pose.pose().map_err(|(key, kind)| LoadError::new(&join(path, key), kind))
+}
+
+/// Largest deviation of a rotation from orthonormality, or of a quaternion from unit length.
+const ROTATION_TOLERANCE: f64 = 1e-6;
+
+/// Pose of a collider in one of the encodings of the test files:
+///
+/// * `collider2origin`, the full row major 4x4 matrix,
+/// * `center` alone,
+/// * `center` with a `rotation` quaternion `[x, y, z, w]`,
+/// * `center` with `euler` angles `[x, y, z]` in radians, applied in XYZ order.
+#[derive(Default)]
+pub(crate) struct PoseFields {
+    pub collider2origin: Option<DMat4>,
+    pub center: Option<DVec3>,
+    pub rotation: Option<DQuat>,
+    pub euler: Option<DVec3>,
+}
+
+impl PoseFields {
+    /// The pose, or the field that is wrong.
+    pub(crate) fn pose(&self) -> Result<DMat4, (&'static str, LoadErrorKind)> {
+        match (self.collider2origin, self.center) {
+            (Some(collider2origin), None) => {
+                if self.rotation.is_some() {
+                    return Err(("rotation", LoadErrorKind::ConflictingField));
+                }
+                if self.euler.is_some() {
+                    return Err(("euler", LoadErrorKind::ConflictingField));
+                }
+                if !collider2origin.is_finite() {
+                    return Err(("collider2origin", LoadErrorKind::NonFinite));
+                }
+                if collider2origin.row(3) != DVec4::W {
+                    return Err(("collider2origin", LoadErrorKind::NotAffine));
+                }
+                validate_rotation(DMat3::from_mat4(collider2origin)).map_err(|kind| ("collider2origin", kind))?;
+                Ok(collider2origin)
+            }
+            (None, Some(center)) => {
+                let rotation = match (self.rotation, self.euler) {
+                    (Some(_), Some(_)) => return Err(("euler", LoadErrorKind::ConflictingField)),
+                    (Some(rotation), None) => {
+                        if (rotation.length() - 1.0).abs() > ROTATION_TOLERANCE {
+                            return Err(("rotation", LoadErrorKind::ImproperRotation));
+                        }
+                        rotation.normalize()
+                    }
+                    (None, Some(euler)) => DQuat::from_euler(EulerRot::XYZ, euler.x, euler.y, euler.z),
+                    (None, None) => DQuat::IDENTITY,
+                };
+                Ok(DMat4::from_rotation_translation(rotation, center))
+            }
+            (Some(_), Some(_)) => Err(("center", LoadErrorKind::ConflictingField)),
+            (None, None) => Err(("collider2origin", LoadErrorKind::MissingField)),
+        }
+    }
+}
+
+fn validate_rotation(rotation: DMat3) -> Result<(), LoadErrorKind> {
+    let determinant = rotation.determinant();
+    if determinant.abs() <= f64::EPSILON {
+        return Err(LoadErrorKind::SingularMatrix);
+    }
+
+    let deviation = (rotation.transpose() * rotation - DMat3::IDENTITY).to_cols_array()
+        .iter()
+        .fold(0.0_f64, |deviation, value| deviation.max(value.abs()));
+    if deviation > ROTATION_TOLERANCE || determinant < 0.0 {
+        return Err(LoadErrorKind::ImproperRotation);
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod test{
-    use glam::dvec3;
+    use glam::{dvec3, DMat3, DMat4, DQuat, EulerRot};
     use serde_json::Value;
 
-    use crate::{colliders::ColliderType, json_loder::{parse_collider, load_test_file}, gjk::GJKNesterov};
+    use crate::{colliders::ColliderType, json_loder::{parse_collider, parse_test_cases, LoadErrorKind}};
 
     #[test]
     fn test_parse_json_collider() {
@@ -106,7 +402,7 @@ mod test{
             "radius": 10.0
         }"#).unwrap();
 
-        let collider = parse_collider(&json_obj);
+        let collider = parse_collider(&json_obj).unwrap();
         assert!(collider.typ == ColliderType::Sphere);
         assert!(collider.center == dvec3(1.0, 0.0, 0.0));
         assert!(collider.radius == 10.0);
@@ -124,9 +420,9 @@ mod test{
             "height": 2.0
         }"#).unwrap();
 
-        let collider = parse_collider(&json_obj);
+        let collider = parse_collider(&json_obj).unwrap();
         assert!(collider.typ == ColliderType::Capluse);
-        // assert!(collider.center == vec3(0.0, 1.0, 2.0)); TODO
+        assert!(collider.center == dvec3(0.0, 1.0, 2.0));
         assert!(collider.radius == 1.0);
         assert!(collider.height == 2.0);
 
@@ -143,11 +439,106 @@ mod test{
             "height": 3.0
         }"#).unwrap();
 
-        let collider = parse_collider(&json_obj);
+        let collider = parse_collider(&json_obj).unwrap();
         assert!(collider.typ == ColliderType::Cylinder);
-        // assert!(collider.center == vec3(5.0, 1.0, 0.0)); TODO
+        assert!(collider.center == dvec3(5.0, 1.0, 0.0));
         assert!(collider.radius == 10.0);
         assert!(collider.height == 3.0);
     }
-}
 
+    #[test]
+    fn test_parse_json_convex_hull() {
+        let json_obj: Value = serde_json::from_str(r#"
+        {
+            "type": "ConvexHull",
+            "collider2origin": [
+                [1.0, 0.0, 0.0, 2.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0]
+            ],
+            "vertices": [
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0],
+                [0.1, 0.1, 0.1]
+            ]
+        }"#).unwrap();
+
+        let collider = parse_collider(&json_obj).unwrap();
+        assert!(collider.typ == ColliderType::ConvexHull);
+        assert!(collider.center == dvec3(2.0, 0.0, 0.0));
+        assert!(collider.hull.vertices.len() == 4);
+        assert!(collider.get_support_point(dvec3(1.0, 0.0, 0.0)) == dvec3(3.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let identity = "[[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]]";
+        let sphere = format!(r#"{{ "type": "Sphere", "collider2origin": {identity}, "radius": 1.0 }}"#);
+
+        let cases = [
+            (format!(r#"[{{ "collider1": {sphere}, "collider2": {sphere}, "distance": 1.0 }}, {{ "collider1": {sphere}, "distance": 1.0 }}]"#),
+                "entry 1: collider2: missing field"),
+            (format!(r#"[{{ "collider1": {sphere}, "collider2": {sphere}, "distance": "1.0" }}]"#),
+                "entry 0: distance: expected number"),
+            (format!(r#"[{{ "collider1": {{ "type": "Torus" }}, "collider2": {sphere}, "distance": 1.0 }}]"#),
+                "entry 0: collider1.type: unknown collider type \"Torus\""),
+            (format!(r#"[{{ "collider1": {sphere}, "collider2": {{ "type": "Box", "collider2origin": {identity}, "size": [1.0, 1.0] }}, "distance": 1.0 }}]"#),
+                "entry 0: collider2.size: expected array of 3 numbers"),
+            (format!(r#"[{{ "collider1": {sphere}, "collider2": {{ "type": "Box", "collider2origin": {identity}, "size": [1.0, 1e400, 1.0] }}, "distance": 1.0 }}]"#),
+                "entry 0: collider2.size[1]: non-finite value"),
+            (format!(r#"[{{ "collider1": {{ "type": "Sphere", "collider2origin": [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]], "radius": 1.0 }}, "collider2": {sphere}, "distance": 1.0 }}]"#),
+                "entry 0: collider1.collider2origin: singular matrix"),
+            (format!(r#"[{{ "collider1": {{ "type": "Capsule", "collider2origin": {identity}, "radius": 1.0, "height": -2.0 }}, "collider2": {sphere}, "distance": 1.0 }}]"#),
+                "entry 0: collider1.height: negative value"),
+            (format!(r#"[{{ "collider1": {sphere}, "collider2": {{ "type": "Box", "collider2origin": {identity}, "size": [1.0, 1.0, -0.5] }}, "distance": 1.0 }}]"#),
+                "entry 0: collider2.size[2]: negative value"),
+            (format!(r#"[{{ "collider1": {sphere}, "collider2": {{ "type": "ConvexHull", "collider2origin": {identity}, "vertices": [] }}, "distance": 1.0 }}]"#),
+                "entry 0: collider2.vertices: empty array"),
+            (r#"{ "collider1": null }"#.to_owned(), "expected array of test cases"),
+        ];
+
+        for (json, message) in cases {
+            let json_data: Value = serde_json::from_str(&json).unwrap();
+            let error = parse_test_cases(&json_data).err().unwrap();
+            assert_eq!(error.to_string(), message);
+        }
+
+        let error = parse_collider(&serde_json::from_str(r#"{ "type": "Capsule", "radius": 1.0 }"#).unwrap()).err().unwrap();
+        assert!(matches!(error.kind, LoadErrorKind::MissingField));
+        assert!(error.entry.is_none() && error.path == "collider2origin");
+    }
+
+    #[test]
+    fn test_parse_pose_encodings() {
+        let rotation = DQuat::from_euler(EulerRot::XYZ, 0.3, -1.1, 2.0);
+        let matrix = DMat4::from_rotation_translation(rotation, dvec3(1.0, 2.0, 3.0)).transpose().to_cols_array_2d();
+
+        let poses = [
+            format!(r#""collider2origin": {matrix:?}"#),
+            format!(r#""center": [1.0, 2.0, 3.0], "rotation": {:?}"#, rotation.to_array()),
+            r#""center": [1.0, 2.0, 3.0], "euler": [0.3, -1.1, 2.0]"#.to_owned(),
+        ];
+        for pose in poses {
+            let json_obj: Value = serde_json::from_str(&format!(r#"{{ "type": "Box", {pose}, "size": [1.0, 2.0, 3.0] }}"#)).unwrap();
+            let collider = parse_collider(&json_obj).unwrap();
+            assert!(collider.center == dvec3(1.0, 2.0, 3.0));
+            assert!(collider.transform.abs_diff_eq(DMat3::from_quat(rotation), 1e-12));
+        }
+
+        let errors = [
+            (r#""collider2origin": [[-1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]]"#, "collider2origin: not a proper rotation"),
+            (r#""collider2origin": [[2.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]]"#, "collider2origin: not a proper rotation"),
+            (r#""collider2origin": [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.5, 0.0, 1.0]]"#, "collider2origin: bottom row is not [0, 0, 0, 1]"),
+            (r#""center": [1.0, 2.0, 3.0], "rotation": [0.0, 0.0, 0.0, 2.0]"#, "rotation: not a proper rotation"),
+            (r#""center": [1.0, 2.0, 3.0], "rotation": [0.0, 0.0, 0.0, 1.0], "euler": [0.0, 0.0, 0.0]"#, "euler: conflicts with another pose field"),
+            (r#""rotation": [0.0, 0.0, 0.0, 1.0]"#, "collider2origin: missing field"),
+        ];
+        for (pose, message) in errors {
+            let json_obj: Value = serde_json::from_str(&format!(r#"{{ "type": "Box", {pose}, "size": [1.0, 2.0, 3.0] }}"#)).unwrap();
+            assert_eq!(parse_collider(&json_obj).err().unwrap().to_string(), message);
+        }
+    }
+}
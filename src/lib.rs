@@ -1,4 +1,15 @@
-
 pub mod colliders;
 pub mod json_loder;
-pub mod gjk;
\ No newline at end of file
+pub mod scalar;
+pub mod support_map;
+pub mod composite;
+mod simplex;
+pub mod gjk;
+pub mod gjk_classic;
+pub mod batch;
+pub mod epa;
+pub mod manifold;
+pub mod persistent_manifold;
+pub mod shape_cast;
+pub mod ray_cast;
+pub mod time_of_impact;
@@ -0,0 +1,534 @@
+use glam::{DVec3, dvec3};
+
+use crate::{
+    colliders::{convex_hull::ConvexHull, Collider, ColliderType},
+    epa::EPA,
+    gjk::GJKNesterov,
+    support_map::SupportMap,
+};
+
+pub(crate) const TOLERANCE: f64 = 1e-6;
+pub(crate) const MAX_ITERATIONS: usize = 100;
+
+/// Maximum number of points of a `ContactManifold`.
+pub const MAX_CONTACTS: usize = 4;
+
+/// Sine of the largest angle between a face normal and the contact normal, or between an edge and
+/// the contact plane, for which the face or edge is used as contact feature instead of a single vertex.
+const FEATURE_ANGLE_SIN: f64 = 0.05;
+
+/// Number of vertices of the polygon the circular caps of cylinders and cones are approximated with.
+const CAP_SEGMENTS: usize = 8;
+
+/// Point of a `ContactManifold`.
+#[derive(Copy, Clone, Debug)]
+pub struct ContactPoint {
+    /// Contact point on the surface of `collider1`.
+    pub point1: DVec3,
+    /// Contact point on the surface of `collider2`.
+    pub point2: DVec3,
+    /// Penetration along the normal, `point1 - point2 = depth * normal`. Negative if the colliders are separated here.
+    pub depth: f64,
+}
+
+/// Contact points of two colliders that share one normal, e.g. the corners of a box resting on the ground.
+#[derive(Clone, Debug)]
+pub struct ContactManifold {
+    /// Unit contact normal pointing from `collider1` to `collider2`.
+    pub normal: DVec3,
+    /// Between 1 and `MAX_CONTACTS` points.
+    pub points: Vec<ContactPoint>,
+}
+
+impl Collider {
+    pub fn contact_manifold(&self, other: &Collider, prediction_distance: f64) -> Option<ContactManifold> {
+        contact_manifold(self, other, prediction_distance, TOLERANCE, MAX_ITERATIONS)
+    }
+}
+
+/// Contact manifold of two colliders that are closer than `prediction_distance`.
+///
+/// The normal and the deepest point come from GJK, or EPA if the colliders overlap. The faces or edges of both
+/// colliders that are nearly perpendicular to the normal are clipped against each other to find the remaining
+/// points, which are reduced to the `MAX_CONTACTS` points spanning the largest area. Shapes without flat features
+/// and contacts at a vertex give the deepest point alone. Exactly touching colliders without a contact normal
+/// give the deepest point alone as well, with the direction between their centers as normal.
+/// Returns `None` if EPA fails on flat overlapping shapes.
+pub fn contact_manifold(
+    collider1: &Collider,
+    collider2: &Collider,
+    prediction_distance: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Option<ContactManifold> {
+    let mut gjk = GJKNesterov::new(None, tolerance);
+    let result = gjk.distance_nesterov_accelerated(collider1, collider2, max_iterations);
+    if result.distance > prediction_distance {
+        return None;
+    }
+
+    let (normal, deepest) = if result.inside() {
+        let penetration = EPA::new(tolerance).penetration(&gjk, collider1, collider2, max_iterations)?;
+        (penetration.normal, ContactPoint { point1: penetration.point1, point2: penetration.point2, depth: penetration.depth })
+    } else {
+        let points = gjk.closest_points();
+        (points.normal, ContactPoint { point1: points.point1, point2: points.point2, depth: -result.distance })
+    };
+    if normal == DVec3::ZERO {
+        let normal = (collider2.center - collider1.center).try_normalize().unwrap_or(DVec3::Z);
+        return Some(ContactManifold { normal, points: vec![deepest] });
+    }
+
+    let mut points = match (Feature::new(collider1, normal), Feature::new(collider2, -normal)) {
+        (Some(feature1), Some(feature2)) => clip(&feature1, &feature2, normal),
+        _ => Vec::new(),
+    };
+    points.retain(|point| point.depth >= -prediction_distance);
+    if points.is_empty() {
+        points.push(deepest);
+    }
+    reduce(&mut points, normal, |point| (point.point1, point.depth));
+
+    Some(ContactManifold { normal, points })
+}
+
+/// Face or edge of the core of a collider that is furthest along a direction, in world space.
+struct Feature {
+    /// Vertices of a convex polygon in cyclic order, or the two end points of an edge.
+    vertices: Vec<DVec3>,
+    /// Outward normal of a face.
+    normal: DVec3,
+    margin: f64,
+}
+
+impl Feature {
+    /// The feature of `collider` along `dir`, `None` if it is a single vertex or a curved surface.
+    fn new(collider: &Collider, dir: DVec3) -> Option<Self> {
+        let local_dir = (collider.transform_transposed * dir).normalize_or_zero();
+        let half_height = 0.5 * collider.height;
+
+        let (vertices, local_normal) = match collider.typ {
+            ColliderType::Box => box_feature(collider.size * 0.5, local_dir)?,
+            ColliderType::RoundedBox => box_feature(collider.size * 0.5 - DVec3::splat(collider.radius), local_dir)?,
+            ColliderType::Capluse => {
+                if local_dir.z.abs() > FEATURE_ANGLE_SIN {
+                    return None;
+                }
+                (vec![dvec3(0.0, 0.0, -half_height), dvec3(0.0, 0.0, half_height)], local_dir)
+            },
+            ColliderType::Cylinder => {
+                if local_dir.z.abs() >= feature_angle_cos() {
+                    let z = half_height.copysign(local_dir.z);
+                    (cap(collider.radius, z), dvec3(0.0, 0.0, 1.0_f64.copysign(local_dir.z)))
+                } else if local_dir.z.abs() <= FEATURE_ANGLE_SIN {
+                    let radial = dvec3(local_dir.x, local_dir.y, 0.0).normalize() * collider.radius;
+                    (vec![radial - dvec3(0.0, 0.0, half_height), radial + dvec3(0.0, 0.0, half_height)], local_dir)
+                } else {
+                    return None;
+                }
+            },
+            ColliderType::Cone => {
+                let radial = dvec3(local_dir.x, local_dir.y, 0.0).normalize_or_zero();
+                let side_normal = (radial * collider.height + dvec3(0.0, 0.0, collider.radius)).normalize();
+                if -local_dir.z >= feature_angle_cos() {
+                    (cap(collider.radius, -half_height), dvec3(0.0, 0.0, -1.0))
+                } else if radial != DVec3::ZERO && local_dir.dot(side_normal) >= feature_angle_cos() {
+                    (vec![dvec3(0.0, 0.0, half_height), radial * collider.radius - dvec3(0.0, 0.0, half_height)], side_normal)
+                } else {
+                    return None;
+                }
+            },
+            ColliderType::ConvexHull => hull_feature(&collider.hull, local_dir)?,
+            ColliderType::Sphere | ColliderType::Ellipsoid => return None,
+        };
+
+        Some(Self {
+            vertices: vertices.into_iter().map(|vertex| collider.center + collider.transform * vertex).collect(),
+            normal: (collider.transform * local_normal).normalize(),
+            margin: collider.margin(),
+        })
+    }
+}
+
+impl Feature {
+    /// `|face normal · dir|`, edges have no face normal and are only used as reference if both features are edges.
+    fn alignment(&self, dir: DVec3) -> f64 {
+        if self.vertices.len() >= 3 {
+            self.normal.dot(dir).abs()
+        } else {
+            0.0
+        }
+    }
+}
+
+fn feature_angle_cos() -> f64 {
+    (1.0 - FEATURE_ANGLE_SIN * FEATURE_ANGLE_SIN).sqrt()
+}
+
+fn box_feature(half_size: DVec3, local_dir: DVec3) -> Option<(Vec<DVec3>, DVec3)> {
+    let abs_dir = local_dir.abs();
+    let axis = if abs_dir.x >= abs_dir.y && abs_dir.x >= abs_dir.z { 0 } else if abs_dir.y >= abs_dir.z { 1 } else { 2 };
+    let corner = DVec3::select(local_dir.cmplt(DVec3::ZERO), -half_size, half_size);
+
+    if abs_dir[axis] >= feature_angle_cos() {
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        let vertices = [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)].map(|(su, sv)| {
+            let mut vertex = corner;
+            vertex[u] = su * half_size[u];
+            vertex[v] = sv * half_size[v];
+            vertex
+        });
+
+        let mut normal = DVec3::ZERO;
+        normal[axis] = 1.0_f64.copysign(local_dir[axis]);
+        return Some((vertices.to_vec(), normal));
+    }
+
+    let mut small_axes = (0..3).filter(|i| abs_dir[*i] <= FEATURE_ANGLE_SIN);
+    match (small_axes.next(), small_axes.next()) {
+        (Some(edge_axis), None) => {
+            let (mut start, mut end) = (corner, corner);
+            start[edge_axis] = -half_size[edge_axis];
+            end[edge_axis] = half_size[edge_axis];
+            Some((vec![start, end], local_dir))
+        },
+        _ => None,
+    }
+}
+
+/// Regular polygon inscribed in the circle of `radius` around the z axis at height `z`.
+fn cap(radius: f64, z: f64) -> Vec<DVec3> {
+    (0..CAP_SEGMENTS)
+        .map(|i| {
+            let angle = std::f64::consts::TAU * i as f64 / CAP_SEGMENTS as f64;
+            dvec3(radius * angle.cos(), radius * angle.sin(), z)
+        })
+        .collect()
+}
+
+fn hull_feature(hull: &ConvexHull, local_dir: DVec3) -> Option<(Vec<DVec3>, DVec3)> {
+    let (face, normal) = hull.faces.iter()
+        .map(|face| {
+            let [a, b, c] = face.map(|i| hull.vertices[i]);
+            (face, (b - a).cross(c - a).normalize_or_zero())
+        })
+        .max_by(|(_, a), (_, b)| a.dot(local_dir).total_cmp(&b.dot(local_dir)))?;
+
+    if normal.dot(local_dir) >= feature_angle_cos() {
+        // The hull is triangulated, all vertices in the plane of the best triangle form the face.
+        let plane = hull.vertices[face[0]].dot(normal);
+        let eps = 1e-9 * hull.bounding_radius().max(1.0);
+        let vertices: Vec<DVec3> = hull.vertices.iter().copied().filter(|vertex| vertex.dot(normal) >= plane - eps).collect();
+
+        let centroid = vertices.iter().sum::<DVec3>() / vertices.len() as f64;
+        let u = (vertices[0] - centroid).normalize();
+        let v = normal.cross(u);
+        let mut vertices = vertices;
+        vertices.sort_by(|a, b| {
+            let angle = |p: &DVec3| (*p - centroid).dot(v).atan2((*p - centroid).dot(u));
+            angle(a).total_cmp(&angle(b))
+        });
+        return Some((vertices, normal));
+    }
+
+    let support = hull.support_index(local_dir);
+    let support_vertex = hull.vertices[support];
+    hull.adjacency[support].iter()
+        .map(|i| hull.vertices[*i])
+        .find(|neighbor| {
+            let edge = *neighbor - support_vertex;
+            edge.dot(local_dir).abs() <= FEATURE_ANGLE_SIN * edge.length()
+        })
+        .map(|neighbor| (vec![support_vertex, neighbor], local_dir))
+}
+
+/// Clips the incident feature against the reference feature, which is the face most aligned with the normal,
+/// and measures the depth of the clipped incident vertices below the reference feature.
+fn clip(feature1: &Feature, feature2: &Feature, normal: DVec3) -> Vec<ContactPoint> {
+    let flipped = feature2.alignment(-normal) > feature1.alignment(normal);
+    let (reference, incident, dir) = if flipped { (feature2, feature1, -normal) } else { (feature1, feature2, normal) };
+
+    let clipped = if reference.vertices.len() >= 3 {
+        clip_polygon(&incident.vertices, &reference.vertices, reference.normal)
+    } else {
+        clip_segment(&incident.vertices, &reference.vertices)
+    };
+
+    clipped.into_iter()
+        .map(|point| {
+            // Distance of the incident vertex from the reference feature along `dir`.
+            let gap = if reference.vertices.len() >= 3 {
+                (point - reference.vertices[0]).dot(reference.normal) / dir.dot(reference.normal)
+            } else {
+                (point - closest_point_on_segment(point, reference.vertices[0], reference.vertices[1])).dot(dir)
+            };
+
+            let reference_point = point - dir * (gap - reference.margin);
+            let incident_point = point - dir * incident.margin;
+            let depth = reference.margin + incident.margin - gap;
+
+            if flipped {
+                ContactPoint { point1: incident_point, point2: reference_point, depth }
+            } else {
+                ContactPoint { point1: reference_point, point2: incident_point, depth }
+            }
+        })
+        .collect()
+}
+
+/// Sutherland-Hodgman clipping of the incident polygon or edge against the side planes of the reference polygon.
+fn clip_polygon(incident: &[DVec3], reference: &[DVec3], reference_normal: DVec3) -> Vec<DVec3> {
+    let centroid = reference.iter().sum::<DVec3>() / reference.len() as f64;
+
+    let mut points = incident.to_vec();
+    let mut clipped = Vec::new();
+    for (i, start) in reference.iter().enumerate() {
+        let end = reference[(i + 1) % reference.len()];
+        let mut side_normal = (end - *start).cross(reference_normal);
+        if side_normal.dot(centroid - *start) > 0.0 {
+            side_normal = -side_normal;
+        }
+        let distance = |point: DVec3| (point - *start).dot(side_normal);
+
+        clipped.clear();
+        let closed = points.len() >= 3;
+        for (j, current) in points.iter().enumerate() {
+            if distance(*current) <= 0.0 {
+                clipped.push(*current);
+            }
+
+            if !closed && j + 1 == points.len() {
+                break;
+            }
+            let next = points[(j + 1) % points.len()];
+            let (d_current, d_next) = (distance(*current), distance(next));
+            if (d_current < 0.0 && d_next > 0.0) || (d_current > 0.0 && d_next < 0.0) {
+                clipped.push(*current + (next - *current) * (d_current / (d_current - d_next)));
+            }
+        }
+        std::mem::swap(&mut points, &mut clipped);
+    }
+    points
+}
+
+/// The part of the incident edge next to the reference edge if both are parallel, nothing if they cross.
+fn clip_segment(incident: &[DVec3], reference: &[DVec3]) -> Vec<DVec3> {
+    let (start, end) = (incident[0], incident[1]);
+    let axis = reference[1] - reference[0];
+    let incident_axis = end - start;
+    if incident_axis.dot(axis).abs() < feature_angle_cos() * incident_axis.length() * axis.length() {
+        return Vec::new();
+    }
+
+    let t = |point: DVec3| (point - reference[0]).dot(axis) / axis.length_squared();
+    let (t_start, t_end) = (t(start), t(end));
+    let (s_min, s_max) = {
+        let s0 = (0.0 - t_start) / (t_end - t_start);
+        let s1 = (1.0 - t_start) / (t_end - t_start);
+        (s0.min(s1).max(0.0), s0.max(s1).min(1.0))
+    };
+    if s_min > s_max {
+        return Vec::new();
+    }
+    vec![start + incident_axis * s_min, start + incident_axis * s_max]
+}
+
+fn closest_point_on_segment(point: DVec3, start: DVec3, end: DVec3) -> DVec3 {
+    let axis = end - start;
+    let t = ((point - start).dot(axis) / axis.length_squared()).clamp(0.0, 1.0);
+    start + axis * t
+}
+
+/// Keeps the deepest point and the points that span the largest area with it,
+/// `contact` gives the position and depth of a point.
+pub(crate) fn reduce<T: Copy>(points: &mut Vec<T>, normal: DVec3, contact: impl Fn(&T) -> (DVec3, f64)) {
+    if points.len() <= MAX_CONTACTS {
+        return;
+    }
+
+    let argmax = |key: &dyn Fn(DVec3, f64) -> f64| {
+        (0..points.len())
+            .map(|i| {
+                let (position, depth) = contact(&points[i]);
+                (i, key(position, depth))
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap().0
+    };
+    let position = |i: usize| contact(&points[i]).0;
+    let area = |a: DVec3, b: DVec3, c: DVec3| (b - a).cross(c - a).dot(normal);
+
+    let a = argmax(&|_, depth| depth);
+    let b = argmax(&|p, _| p.distance_squared(position(a)));
+    let c = argmax(&|p, _| area(position(a), position(b), p).abs());
+
+    // The fourth point lies furthest outside of the triangle.
+    let (pa, pb, pc) = (position(a), position(b), position(c));
+    let sign = area(pa, pb, pc).signum();
+    let d = argmax(&|p, _| (-sign * area(pa, pb, p)).max(-sign * area(pb, pc, p)).max(-sign * area(pc, pa, p)));
+
+    let mut selected = vec![a, b, c, d];
+    selected.sort_unstable();
+    selected.dedup();
+    *points = selected.into_iter().map(|i| points[i]).collect();
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::f64::consts::FRAC_PI_2;
+
+    use glam::{dvec3, DMat4, DQuat, DVec3};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::colliders::Collider;
+
+    use super::{clip, Feature, MAX_CONTACTS};
+
+    /// A large box whose top face is the plane `z = 0`.
+    pub(crate) fn ground() -> Collider {
+        Collider::new_box(DMat4::from_translation(dvec3(0.0, 0.0, -0.5)), dvec3(10.0, 10.0, 1.0))
+    }
+
+    #[test]
+    fn test_box_on_ground() {
+        let pose = DMat4::from_rotation_translation(DQuat::from_rotation_z(0.3), dvec3(1.0, 2.0, 0.49));
+        let collider = Collider::new_box(pose, dvec3(1.0, 1.0, 1.0));
+
+        let manifold = ground().contact_manifold(&collider, 0.0).unwrap();
+        assert!(manifold.normal.distance(dvec3(0.0, 0.0, 1.0)) < 1e-6);
+        assert!(manifold.points.len() == 4);
+        for point in manifold.points.iter() {
+            assert!((point.depth - 0.01).abs() < 1e-6);
+            assert!(point.point1.z.abs() < 1e-6);
+            assert!(point.point1.distance(point.point2 + manifold.normal * point.depth) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_tilted_box_on_ground() {
+        // Resting on an edge, the edge and the ground face give two points.
+        let pose = DMat4::from_rotation_translation(DQuat::from_rotation_x(0.4), dvec3(0.0, 0.0, 0.0));
+        let collider = Collider::new_box(pose, dvec3(1.0, 1.0, 1.0));
+        let lowest = collider.get_support_point(dvec3(0.0, 0.0, -1.0)).z;
+        let collider = collider.with_transform(DMat4::from_translation(dvec3(0.0, 0.0, -lowest - 0.01)) * pose);
+
+        let manifold = ground().contact_manifold(&collider, 0.0).unwrap();
+        assert!(manifold.points.len() == 2);
+        assert!(manifold.points.iter().all(|point| (point.depth - 0.01).abs() < 1e-6));
+        assert!((manifold.points[0].point1.x - manifold.points[1].point1.x).abs() > 0.99);
+    }
+
+    #[test]
+    fn test_capsule_on_ground() {
+        let pose = DMat4::from_rotation_translation(DQuat::from_rotation_x(FRAC_PI_2), dvec3(0.0, 0.0, 0.49));
+        let capsule = Collider::new_capluse(pose, 0.5, 2.0);
+
+        let manifold = ground().contact_manifold(&capsule, 0.0).unwrap();
+        assert!(manifold.points.len() == 2);
+        for point in manifold.points.iter() {
+            assert!((point.depth - 0.01).abs() < 1e-6);
+            assert!((point.point1.y.abs() - 1.0).abs() < 1e-6);
+        }
+
+        // Standing upright it touches at one point.
+        let standing = Collider::new_capluse(DMat4::from_translation(dvec3(0.0, 0.0, 1.49)), 0.5, 2.0);
+        assert!(ground().contact_manifold(&standing, 0.0).unwrap().points.len() == 1);
+    }
+
+    #[test]
+    fn test_cylinder_on_ground() {
+        let cylinder = Collider::new_cylinder(DMat4::from_translation(dvec3(0.0, 0.0, 0.99)), 0.5, 2.0);
+
+        let manifold = cylinder.contact_manifold(&ground(), 0.0).unwrap();
+        assert!(manifold.normal.distance(dvec3(0.0, 0.0, -1.0)) < 1e-6);
+        assert!(manifold.points.len() == 4);
+        for point in manifold.points.iter() {
+            assert!((point.depth - 0.01).abs() < 1e-6);
+            assert!((point.point1.truncate().length() - 0.5).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_hull_on_ground() {
+        // A cube with a pyramid on top, its bottom face is split into two triangles.
+        let mut vertices = vec![dvec3(0.0, 0.0, 0.9)];
+        for i in 0..8 {
+            vertices.push(dvec3(if i & 1 == 0 { -0.5 } else { 0.5 }, if i & 2 == 0 { -0.5 } else { 0.5 }, if i & 4 == 0 { -0.5 } else { 0.5 }));
+        }
+        let hull = Collider::new_convex_hull(DMat4::from_translation(dvec3(0.0, 0.0, 0.49)), &vertices);
+
+        let manifold = hull.contact_manifold(&ground(), 0.0).unwrap();
+        assert!(manifold.points.len() == 4);
+        for point in manifold.points.iter() {
+            assert!((point.depth - 0.01).abs() < 1e-6);
+            assert!(point.point2.truncate().abs().distance(dvec3(0.5, 0.5, 0.0).truncate()) < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_reference_is_the_aligned_face() {
+        // A large triangle facing the normal exactly, below a slightly tilted square with more vertices.
+        let normal = dvec3(0.0, 0.0, 1.0);
+        let triangle = Feature {
+            vertices: vec![dvec3(-10.0, -10.0, 0.0), dvec3(10.0, -10.0, 0.0), dvec3(0.0, 10.0, 0.0)],
+            normal,
+            margin: 0.0,
+        };
+        let rotation = DQuat::from_rotation_x(0.03);
+        let square = Feature {
+            vertices: [dvec3(-0.5, -0.5, 0.0), dvec3(-0.5, 0.5, 0.0), dvec3(0.5, 0.5, 0.0), dvec3(0.5, -0.5, 0.0)]
+                .into_iter()
+                .map(|vertex| rotation * vertex - dvec3(0.0, 0.0, 0.01))
+                .collect(),
+            normal: rotation * -normal,
+            margin: 0.0,
+        };
+
+        let points = clip(&triangle, &square, normal);
+        assert!(points.len() == 4);
+        for point in points.iter() {
+            assert!(square.vertices.iter().any(|vertex| vertex.distance(point.point2) < 1e-12));
+            assert!(point.point1.z.abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_exactly_touching_boxes() {
+        let box1 = Collider::new_box(DMat4::IDENTITY, dvec3(1.0, 1.0, 1.0));
+        let box2 = Collider::new_box(DMat4::from_translation(dvec3(1.0, 0.0, 0.0)), dvec3(1.0, 1.0, 1.0));
+
+        let manifold = box1.contact_manifold(&box2, 0.0).unwrap();
+        assert!(manifold.normal.distance(DVec3::X) < 1e-9);
+        assert!(manifold.points.len() == 4);
+        assert!(manifold.points.iter().all(|point| point.depth.abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_sphere_on_ground() {
+        let sphere = Collider::new_sphere(DMat4::from_translation(dvec3(0.0, 0.0, 0.45)), 0.5);
+
+        let manifold = ground().contact_manifold(&sphere, 0.0).unwrap();
+        assert!(manifold.points.len() == 1);
+        assert!((manifold.points[0].depth - 0.05).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_random_manifolds() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..2000 {
+            let collider1 = Collider::new_random_any(&mut rng, 0.5..3.0);
+            let collider2 = Collider::new_random_any(&mut rng, 0.5..3.0);
+
+            let Some(manifold) = collider1.contact_manifold(&collider2, 0.1) else {
+                continue;
+            };
+            assert!(!manifold.points.is_empty() && manifold.points.len() <= MAX_CONTACTS);
+            assert!((manifold.normal.length() - 1.0).abs() < 1e-9);
+            for point in manifold.points.iter() {
+                assert!(point.depth >= -0.1);
+                assert!(point.point1.distance(point.point2 + manifold.normal * point.depth) < 1e-6);
+            }
+        }
+    }
+}
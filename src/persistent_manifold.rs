@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+
+use glam::DVec3;
+
+use crate::{
+    colliders::Collider,
+    manifold::{contact_manifold, reduce, ContactManifold, MAX_ITERATIONS, TOLERANCE},
+};
+
+/// Contact point that is tracked over several frames.
+#[derive(Copy, Clone, Debug)]
+pub struct PersistentContact {
+    /// Contact point on `collider1` in its local space.
+    pub local_point1: DVec3,
+    /// Contact point on `collider2` in its local space.
+    pub local_point2: DVec3,
+    /// Contact point on `collider1` in world space.
+    pub point1: DVec3,
+    /// Contact point on `collider2` in world space.
+    pub point2: DVec3,
+    /// Penetration along the normal of the manifold, negative if the colliders are separated here.
+    pub depth: f64,
+    /// Impulse the solver applied along the normal, kept to warm start the next frame.
+    pub normal_impulse: f64,
+    /// Impulses the solver applied along its two friction directions.
+    pub tangent_impulses: [f64; 2],
+    /// Number of frames the point has been matched.
+    pub lifetime: usize,
+}
+
+impl PersistentContact {
+    fn new(point1: DVec3, point2: DVec3, depth: f64, collider1: &Collider, collider2: &Collider) -> Self {
+        Self {
+            local_point1: to_local(collider1, point1),
+            local_point2: to_local(collider2, point2),
+            point1,
+            point2,
+            depth,
+            normal_impulse: 0.0,
+            tangent_impulses: [0.0; 2],
+            lifetime: 0,
+        }
+    }
+}
+
+/// Contact manifold of a collider pair that persists between frames, as in Bullet's `btPersistentManifold`.
+///
+/// Every frame the new contact points are matched with the previous ones by their local positions on `collider1`,
+/// matched points keep their accumulated impulses. Previous points that are not found again stay in the manifold
+/// while the colliders move with them, so e.g. a box on a single contact point per frame still builds a stable
+/// manifold. Points are dropped once they separate along the normal or drift apart tangentially by more than the
+/// breaking threshold.
+#[derive(Clone, Debug)]
+pub struct PersistentManifold {
+    /// Unit contact normal pointing from `collider1` to `collider2`.
+    pub normal: DVec3,
+    /// Up to `MAX_CONTACTS` points.
+    pub points: Vec<PersistentContact>,
+    breaking_threshold: f64,
+}
+
+impl PersistentManifold {
+    pub fn new(breaking_threshold: f64) -> Self {
+        Self {
+            normal: DVec3::ZERO,
+            points: Vec::new(),
+            breaking_threshold,
+        }
+    }
+
+    pub fn breaking_threshold(&self) -> f64 {
+        self.breaking_threshold
+    }
+
+    /// Merges the contacts of this frame into the manifold. `manifold` is `None` if the colliders are apart, which drops all points.
+    pub fn update(&mut self, collider1: &Collider, collider2: &Collider, manifold: Option<&ContactManifold>) {
+        let Some(manifold) = manifold else {
+            self.points.clear();
+            return;
+        };
+        self.normal = manifold.normal;
+
+        // Every previous point is matched at most once, further new points close to it are added as new points.
+        let mut claimed = vec![false; self.points.len()];
+        for point in manifold.points.iter() {
+            let new = PersistentContact::new(point.point1, point.point2, point.depth, collider1, collider2);
+            match self.closest_point(new.local_point1, &claimed) {
+                Some(i) => {
+                    claimed[i] = true;
+                    let old = self.points[i];
+                    self.points[i] = PersistentContact {
+                        normal_impulse: old.normal_impulse,
+                        tangent_impulses: old.tangent_impulses,
+                        lifetime: old.lifetime + 1,
+                        ..new
+                    };
+                },
+                None => self.points.push(new),
+            }
+        }
+
+        self.refresh(collider1, collider2);
+        reduce(&mut self.points, self.normal, |point| (point.point1, point.depth));
+    }
+
+    /// Index of the previous point closest to `local_point1` within the breaking threshold that is not `claimed` yet.
+    fn closest_point(&self, local_point1: DVec3, claimed: &[bool]) -> Option<usize> {
+        let threshold_squared = self.breaking_threshold * self.breaking_threshold;
+        self.points.iter()
+            .enumerate()
+            .take(claimed.len())
+            .filter(|(i, _)| !claimed[*i])
+            .map(|(i, point)| (i, point.local_point1.distance_squared(local_point1)))
+            .filter(|(_, distance_squared)| *distance_squared < threshold_squared)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i)
+    }
+
+    /// Moves the points with the colliders and drops those that drifted too far.
+    fn refresh(&mut self, collider1: &Collider, collider2: &Collider) {
+        let normal = self.normal;
+        let threshold = self.breaking_threshold;
+
+        self.points.retain_mut(|point| {
+            point.point1 = to_world(collider1, point.local_point1);
+            point.point2 = to_world(collider2, point.local_point2);
+            point.depth = (point.point1 - point.point2).dot(normal);
+
+            let drift = point.point1 - point.point2 - normal * point.depth;
+            point.depth > -threshold && drift.length_squared() < threshold * threshold
+        });
+    }
+}
+
+/// Persistent manifolds of many collider pairs, keyed by the indices of the colliders.
+#[derive(Clone, Debug)]
+pub struct ManifoldCache {
+    manifolds: HashMap<(usize, usize), PersistentManifold>,
+    prediction_distance: f64,
+    breaking_threshold: f64,
+}
+
+impl ManifoldCache {
+    /// Contacts are generated up to `prediction_distance` and kept until they drift by `breaking_threshold`.
+    pub fn new(prediction_distance: f64, breaking_threshold: f64) -> Self {
+        Self {
+            manifolds: HashMap::new(),
+            prediction_distance,
+            breaking_threshold,
+        }
+    }
+
+    /// Generates the contacts of the pair `key` for this frame and merges them into its manifold.
+    /// The pair is removed and `None` returned once it has no points left.
+    pub fn update(&mut self, key: (usize, usize), collider1: &Collider, collider2: &Collider) -> Option<&mut PersistentManifold> {
+        let manifold = contact_manifold(collider1, collider2, self.prediction_distance, TOLERANCE, MAX_ITERATIONS);
+
+        let persistent = self.manifolds.entry(key).or_insert_with(|| PersistentManifold::new(self.breaking_threshold));
+        persistent.update(collider1, collider2, manifold.as_ref());
+
+        if persistent.points.is_empty() {
+            self.manifolds.remove(&key);
+            return None;
+        }
+        self.manifolds.get_mut(&key)
+    }
+
+    pub fn get(&self, key: (usize, usize)) -> Option<&PersistentManifold> {
+        self.manifolds.get(&key)
+    }
+
+    pub fn get_mut(&mut self, key: (usize, usize)) -> Option<&mut PersistentManifold> {
+        self.manifolds.get_mut(&key)
+    }
+
+    /// Forgets a pair, e.g. when the broad phase no longer reports it.
+    pub fn remove(&mut self, key: (usize, usize)) -> Option<PersistentManifold> {
+        self.manifolds.remove(&key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.manifolds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.manifolds.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&(usize, usize), &PersistentManifold)> {
+        self.manifolds.iter()
+    }
+}
+
+fn to_local(collider: &Collider, point: DVec3) -> DVec3 {
+    collider.transform_transposed * (point - collider.center)
+}
+
+fn to_world(collider: &Collider, local_point: DVec3) -> DVec3 {
+    collider.center + collider.transform * local_point
+}
+
+#[cfg(test)]
+mod test {
+    use glam::{dvec3, DMat4};
+
+    use crate::{colliders::Collider, manifold::{test::ground, ContactManifold, ContactPoint}};
+
+    use super::{ManifoldCache, PersistentManifold};
+
+    #[test]
+    fn test_impulses_persist() {
+        let mut cache = ManifoldCache::new(0.01, 0.02);
+        let ground = ground();
+        let size = dvec3(1.0, 1.0, 1.0);
+
+        let manifold = cache.update((0, 1), &ground, &Collider::new_box(DMat4::from_translation(dvec3(0.0, 0.0, 0.499)), size)).unwrap();
+        assert!(manifold.points.len() == 4);
+        for point in manifold.points.iter_mut() {
+            point.normal_impulse = 1.0;
+        }
+
+        // Sliding a little keeps the points and their impulses.
+        let manifold = cache.update((0, 1), &ground, &Collider::new_box(DMat4::from_translation(dvec3(0.005, 0.0, 0.499)), size)).unwrap();
+        assert!(manifold.points.len() == 4);
+        assert!(manifold.points.iter().all(|point| point.normal_impulse == 1.0 && point.lifetime == 1));
+
+        // Jumping further than the breaking threshold replaces them.
+        let manifold = cache.update((0, 1), &ground, &Collider::new_box(DMat4::from_translation(dvec3(0.5, 0.0, 0.499)), size)).unwrap();
+        assert!(manifold.points.len() == 4);
+        assert!(manifold.points.iter().all(|point| point.normal_impulse == 0.0 && point.lifetime == 0));
+
+        // Lifting the box removes the pair.
+        assert!(cache.update((0, 1), &ground, &Collider::new_box(DMat4::from_translation(dvec3(0.5, 0.0, 1.0)), size)).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_points_accumulate() {
+        // A contact generator that reports one corner of a resting box per frame still builds a full manifold.
+        let ground = ground();
+        let collider = Collider::new_box(DMat4::from_translation(dvec3(0.0, 0.0, 0.499)), dvec3(1.0, 1.0, 1.0));
+        let mut persistent = PersistentManifold::new(0.02);
+
+        let corners = [(0.5, 0.5), (-0.5, 0.5), (0.0, 0.0), (-0.5, -0.5), (0.5, -0.5), (0.5, 0.5)];
+        for (i, (x, y)) in corners.into_iter().enumerate() {
+            let manifold = ContactManifold {
+                normal: dvec3(0.0, 0.0, 1.0),
+                points: vec![ContactPoint { point1: dvec3(x, y, 0.0), point2: dvec3(x, y, -0.001), depth: 0.001 }],
+            };
+            persistent.update(&ground, &collider, Some(&manifold));
+            assert!(persistent.points.len() == [1, 2, 3, 4, 4, 4][i]);
+        }
+
+        // The center point spans no area with the corners and was replaced, the first corner was matched again.
+        assert!(persistent.points.iter().all(|point| point.point1.x.abs() == 0.5 && point.point1.y.abs() == 0.5));
+        assert!(persistent.points.iter().any(|point| point.lifetime == 1));
+        assert!(persistent.points.iter().all(|point| (point.depth - 0.001).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_old_point_is_matched_once() {
+        let ground = ground();
+        let collider = Collider::new_box(DMat4::from_translation(dvec3(0.0, 0.0, 0.499)), dvec3(1.0, 1.0, 1.0));
+        let mut persistent = PersistentManifold::new(0.02);
+        let contact = |x: f64| ContactPoint { point1: dvec3(x, 0.5, 0.0), point2: dvec3(x, 0.5, -0.001), depth: 0.001 };
+
+        persistent.update(&ground, &collider, Some(&ContactManifold { normal: dvec3(0.0, 0.0, 1.0), points: vec![contact(0.5)] }));
+        persistent.points[0].normal_impulse = 1.0;
+
+        // Both new points are close to the old one, only the first inherits it.
+        let manifold = ContactManifold { normal: dvec3(0.0, 0.0, 1.0), points: vec![contact(0.5), contact(0.49)] };
+        persistent.update(&ground, &collider, Some(&manifold));
+        assert!(persistent.points.len() == 2);
+        assert!(persistent.points.iter().filter(|point| point.normal_impulse == 1.0 && point.lifetime == 1).count() == 1);
+        assert!(persistent.points.iter().any(|point| point.point1.x == 0.49 && point.lifetime == 0));
+    }
+}
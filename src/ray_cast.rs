@@ -0,0 +1,85 @@
+use glam::DVec3;
+
+use crate::{colliders::Collider, shape_cast::shape_cast, support_map::SupportMap};
+
+const TOLERANCE: f64 = 1e-6;
+const MAX_ITERATIONS: usize = 100;
+
+/// Hit of a ray with a shape.
+#[derive(Copy, Clone, Debug)]
+pub struct RayHit {
+    /// Ray parameter of the hit, the hit point is `origin + toi * dir`.
+    pub toi: f64,
+    pub point: DVec3,
+    /// Outward unit normal of the surface at `point`, zero if the ray starts inside the shape.
+    pub normal: DVec3,
+}
+
+impl Collider {
+    pub fn ray_cast(&self, origin: DVec3, dir: DVec3, max_toi: f64) -> Option<RayHit> {
+        ray_cast(self, origin, dir, max_toi, TOLERANCE, MAX_ITERATIONS)
+    }
+}
+
+/// Casts the ray `origin + t * dir` for `t` in `[0, max_toi]` against any shape.
+/// A ray is the cast of a single point, so this is a [`shape_cast`] of `origin` with velocity `dir`.
+pub fn ray_cast<S: SupportMap + ?Sized>(
+    shape: &S,
+    origin: DVec3,
+    dir: DVec3,
+    max_toi: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Option<RayHit> {
+    let hit = shape_cast(&Point(origin), shape, dir, max_toi, tolerance, max_iterations)?;
+
+    Some(RayHit {
+        toi: hit.toi,
+        point: origin + hit.toi * dir,
+        normal: -hit.normal,
+    })
+}
+
+struct Point(DVec3);
+
+impl SupportMap for Point {
+    fn support_point(&self, _dir: DVec3) -> DVec3 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::{dvec3, DMat4, DQuat, DVec3};
+
+    use crate::colliders::Collider;
+
+    #[test]
+    fn test_ray_cast_sphere() {
+        let sphere = Collider::new_sphere(DMat4::from_translation(dvec3(5.0, 0.0, 0.0)), 1.0);
+
+        let hit = sphere.ray_cast(DVec3::ZERO, dvec3(1.0, 0.0, 0.0), 100.0).unwrap();
+        assert!((hit.toi - 4.0).abs() < 1e-5);
+        assert!(hit.point.distance(dvec3(4.0, 0.0, 0.0)) < 1e-5);
+        assert!(hit.normal.distance(dvec3(-1.0, 0.0, 0.0)) < 1e-3);
+
+        assert!(sphere.ray_cast(DVec3::ZERO, dvec3(1.0, 0.0, 0.0), 3.0).is_none());
+        assert!(sphere.ray_cast(DVec3::ZERO, dvec3(-1.0, 0.0, 0.0), 100.0).is_none());
+        assert!(sphere.ray_cast(DVec3::ZERO, dvec3(0.0, 1.0, 0.0), 100.0).is_none());
+    }
+
+    #[test]
+    fn test_ray_cast_box() {
+        let collider = Collider::new_box(
+            DMat4::from_rotation_translation(DQuat::from_rotation_z(0.3), dvec3(0.0, 0.0, 3.0)),
+            dvec3(2.0, 2.0, 2.0));
+
+        let hit = collider.ray_cast(dvec3(0.2, 0.1, 0.0), dvec3(0.0, 0.0, 2.0), 10.0).unwrap();
+        assert!((hit.toi - 1.0).abs() < 1e-6);
+        assert!(hit.normal.distance(dvec3(0.0, 0.0, -1.0)) < 1e-6);
+
+        let hit = collider.ray_cast(dvec3(0.0, 0.0, 3.0), dvec3(0.0, 0.0, 1.0), 10.0).unwrap();
+        assert!(hit.toi == 0.0);
+        assert!(hit.normal == DVec3::ZERO);
+    }
+}
@@ -0,0 +1,208 @@
+use std::{cmp::Ordering, fmt::Debug, ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign}};
+
+use glam::{DMat3, DVec3, Mat3A, Vec3A};
+
+/// Floating point type the colliders, their support functions and `GJKNesterov` are generic over.
+///
+/// `f64` works on `DVec3`/`DMat3`, `f32` on the SIMD aligned `Vec3A`/`Mat3A` of engines that keep their poses in
+/// single precision. Results are reported in `f64` either way, which is exact for `f32`.
+pub trait Scalar:
+    Copy + Debug + Default + PartialOrd + Send + Sync + 'static
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self>
+{
+    type Vec3: Vector3<Self>;
+    type Mat3: Matrix3<Self>;
+
+    const ZERO: Self;
+    const HALF: Self;
+    const ONE: Self;
+    const INFINITY: Self;
+
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+/// The subset of the glam vector API used by the generic code.
+pub trait Vector3<F: Scalar>:
+    Copy + Debug + Default + PartialEq + Send + Sync + 'static
+    + Add<Output = Self> + Sub<Output = Self> + Neg<Output = Self>
+    + Mul<Output = Self> + Mul<F, Output = Self> + Div<F, Output = Self>
+    + AddAssign + SubAssign
+{
+    const ZERO: Self;
+    const X: Self;
+
+    fn new(x: F, y: F, z: F) -> Self;
+    fn splat(value: F) -> Self;
+    fn x(self) -> F;
+    fn y(self) -> F;
+    fn z(self) -> F;
+
+    fn dot(self, rhs: Self) -> F;
+    fn cross(self, rhs: Self) -> Self;
+    fn length(self) -> F;
+    fn length_squared(self) -> F;
+    fn normalize(self) -> Self;
+    fn normalize_or_zero(self) -> Self;
+    fn abs(self) -> Self;
+    fn max_element(self) -> F;
+    fn is_finite(self) -> bool;
+
+    fn from_dvec3(v: DVec3) -> Self;
+    fn as_dvec3(self) -> DVec3;
+}
+
+/// The subset of the glam 3x3 matrix API used by the generic code.
+pub trait Matrix3<F: Scalar>: Copy + Debug + Send + Sync + 'static + Mul<F::Vec3, Output = F::Vec3> {
+    fn from_cols(x_axis: F::Vec3, y_axis: F::Vec3, z_axis: F::Vec3) -> Self;
+    fn transpose(&self) -> Self;
+    fn determinant(&self) -> F;
+    fn inverse(&self) -> Self;
+
+    fn from_dmat3(m: DMat3) -> Self;
+}
+
+macro_rules! impl_scalar {
+    ($scalar:ty, $vec3:ty, $mat3:ty) => {
+        impl Scalar for $scalar {
+            type Vec3 = $vec3;
+            type Mat3 = $mat3;
+
+            const ZERO: Self = 0.0;
+            const HALF: Self = 0.5;
+            const ONE: Self = 1.0;
+            const INFINITY: Self = <$scalar>::INFINITY;
+
+            fn from_f64(value: f64) -> Self {
+                value as $scalar
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn sqrt(self) -> Self {
+                <$scalar>::sqrt(self)
+            }
+
+            fn abs(self) -> Self {
+                <$scalar>::abs(self)
+            }
+
+            fn max(self, other: Self) -> Self {
+                <$scalar>::max(self, other)
+            }
+
+            fn min(self, other: Self) -> Self {
+                <$scalar>::min(self, other)
+            }
+
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                <$scalar>::total_cmp(self, other)
+            }
+        }
+
+        impl Vector3<$scalar> for $vec3 {
+            const ZERO: Self = <$vec3>::ZERO;
+            const X: Self = <$vec3>::X;
+
+            fn new(x: $scalar, y: $scalar, z: $scalar) -> Self {
+                <$vec3>::new(x, y, z)
+            }
+
+            fn splat(value: $scalar) -> Self {
+                <$vec3>::splat(value)
+            }
+
+            fn x(self) -> $scalar {
+                self.x
+            }
+
+            fn y(self) -> $scalar {
+                self.y
+            }
+
+            fn z(self) -> $scalar {
+                self.z
+            }
+
+            fn dot(self, rhs: Self) -> $scalar {
+                <$vec3>::dot(self, rhs)
+            }
+
+            fn cross(self, rhs: Self) -> Self {
+                <$vec3>::cross(self, rhs)
+            }
+
+            fn length(self) -> $scalar {
+                <$vec3>::length(self)
+            }
+
+            fn length_squared(self) -> $scalar {
+                <$vec3>::length_squared(self)
+            }
+
+            fn normalize(self) -> Self {
+                <$vec3>::normalize(self)
+            }
+
+            fn normalize_or_zero(self) -> Self {
+                <$vec3>::normalize_or_zero(self)
+            }
+
+            fn abs(self) -> Self {
+                <$vec3>::abs(self)
+            }
+
+            fn max_element(self) -> $scalar {
+                <$vec3>::max_element(self)
+            }
+
+            fn is_finite(self) -> bool {
+                <$vec3>::is_finite(self)
+            }
+
+            fn from_dvec3(v: DVec3) -> Self {
+                <$vec3>::new(v.x as $scalar, v.y as $scalar, v.z as $scalar)
+            }
+
+            fn as_dvec3(self) -> DVec3 {
+                DVec3::new(self.x as f64, self.y as f64, self.z as f64)
+            }
+        }
+
+        impl Matrix3<$scalar> for $mat3 {
+            fn from_cols(x_axis: $vec3, y_axis: $vec3, z_axis: $vec3) -> Self {
+                <$mat3>::from_cols(x_axis, y_axis, z_axis)
+            }
+
+            fn transpose(&self) -> Self {
+                <$mat3>::transpose(self)
+            }
+
+            fn determinant(&self) -> $scalar {
+                <$mat3>::determinant(self)
+            }
+
+            fn inverse(&self) -> Self {
+                <$mat3>::inverse(self)
+            }
+
+            fn from_dmat3(m: DMat3) -> Self {
+                <$mat3>::from_cols(
+                    <$vec3 as Vector3<$scalar>>::from_dvec3(m.x_axis),
+                    <$vec3 as Vector3<$scalar>>::from_dvec3(m.y_axis),
+                    <$vec3 as Vector3<$scalar>>::from_dvec3(m.z_axis))
+            }
+        }
+    };
+}
+
+impl_scalar!(f64, DVec3, DMat3);
+impl_scalar!(f32, Vec3A, Mat3A);
@@ -0,0 +1,165 @@
+use glam::DVec3;
+
+use crate::{colliders::Collider, simplex::{Simplex, Vertex}, support_map::SupportMap};
+
+const TOLERANCE: f64 = 1e-6;
+const MAX_ITERATIONS: usize = 100;
+
+/// First contact of a shape moving linearly towards another one.
+#[derive(Copy, Clone, Debug)]
+pub struct ShapeCastHit {
+    /// Time of impact, `collider1` touches `collider2` after moving by `toi * velocity`.
+    pub toi: f64,
+    /// Unit contact normal pointing from `collider1` to `collider2`, zero if they overlap at the start.
+    pub normal: DVec3,
+    /// Contact point on `collider1` at the time of impact.
+    pub point1: DVec3,
+    /// Contact point on `collider2`.
+    pub point2: DVec3,
+}
+
+impl Collider {
+    pub fn shape_cast(&self, other: &Collider, velocity: DVec3, max_toi: f64) -> Option<ShapeCastHit> {
+        shape_cast(self, other, velocity, max_toi, TOLERANCE, MAX_ITERATIONS)
+    }
+}
+
+/// Sweeps `collider1` with the relative `velocity` against a static `collider2` and returns the
+/// first contact for a time in `[0, max_toi]`.
+///
+/// This is the GJK ray cast of van den Bergen, casting the ray `t * velocity` from the origin
+/// against the Minkowski difference `collider2 - collider1`.
+/// The simplex vertices are `x - (b - a)`, where `x` is the current point on the ray, stored with `s0 = a + x`
+/// and `s1 = b` so that the barycentric coordinates give the contact points on both shapes.
+///
+/// Returns `None` on a miss and also if the cast did not converge within `max_iterations`,
+/// as the unconverged `toi` of a grazing miss would otherwise be reported as a hit.
+pub fn shape_cast<A: SupportMap + ?Sized, B: SupportMap + ?Sized>(
+    collider1: &A,
+    collider2: &B,
+    velocity: DVec3,
+    max_toi: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Option<ShapeCastHit> {
+    let mut toi = 0.0;
+    let mut x = DVec3::ZERO;
+    let mut normal = DVec3::ZERO;
+
+    let mut simplex = Simplex::new();
+    let mut v = collider1.support_point(velocity) - collider2.support_point(-velocity);
+    let velocity_len = velocity.length();
+    let mut converged = false;
+
+    for _ in 0..max_iterations {
+        if v.length_squared() <= tolerance * tolerance {
+            converged = true;
+            break;
+        }
+
+        let support = Vertex::new(collider1.support_point(-v) + x, collider2.support_point(v));
+
+        let v_dot_w = v.dot(support.v);
+        if v_dot_w > 0.0 {
+            // The support plane separates x from the Minkowski difference, advance x onto it.
+            let v_dot_velocity = v.dot(velocity);
+            if v_dot_velocity >= 0.0 {
+                return None;
+            }
+
+            // The separation gap along v is a lower bound of the distance x still has to travel.
+            if v_dot_w > (max_toi - toi) * velocity_len * v.length() {
+                return None;
+            }
+
+            toi -= v_dot_w / v_dot_velocity;
+            if toi > max_toi {
+                return None;
+            }
+
+            let new_x = toi * velocity;
+            for vertex in simplex.vertices.iter_mut().take(simplex.len) {
+                *vertex = Vertex::new(vertex.s0 + new_x - x, vertex.s1);
+            }
+            simplex.push(Vertex::new(support.s0 + new_x - x, support.s1));
+
+            x = new_x;
+            normal = -v;
+        } else {
+            simplex.push(support);
+        }
+
+        if simplex.project_origen() {
+            converged = true;
+            break;
+        }
+        v = simplex.ray;
+    }
+
+    if !converged {
+        return None;
+    }
+
+    let (point1, point2) = if simplex.len == 0 {
+        (collider1.support_point(velocity) + x, collider2.support_point(-velocity))
+    } else {
+        simplex.closest_points()
+    };
+
+    Some(ShapeCastHit {
+        toi,
+        normal: normal.normalize_or_zero(),
+        point1,
+        point2,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::FRAC_PI_2;
+
+    use glam::{dvec3, DMat4, DQuat};
+
+    use crate::colliders::Collider;
+
+    use super::shape_cast;
+
+    #[test]
+    fn test_shape_cast_spheres() {
+        let collider1 = Collider::new_sphere(DMat4::IDENTITY, 1.0);
+        let collider2 = Collider::new_sphere(DMat4::from_translation(dvec3(5.0, 0.0, 0.0)), 1.0);
+
+        let hit = collider1.shape_cast(&collider2, dvec3(2.0, 0.0, 0.0), 10.0).unwrap();
+        assert!((hit.toi - 1.5).abs() < 1e-5);
+        assert!(hit.normal.distance(dvec3(1.0, 0.0, 0.0)) < 1e-3);
+        assert!(hit.point1.distance(dvec3(4.0, 0.0, 0.0)) < 1e-3);
+        assert!(hit.point2.distance(dvec3(4.0, 0.0, 0.0)) < 1e-3);
+
+        assert!(collider1.shape_cast(&collider2, dvec3(2.0, 0.0, 0.0), 1.0).is_none());
+        assert!(collider1.shape_cast(&collider2, dvec3(0.0, 2.0, 0.0), 10.0).is_none());
+    }
+
+    #[test]
+    fn test_shape_cast_capsule_against_box() {
+        let capsule = Collider::new_capluse(
+            DMat4::from_rotation_translation(DQuat::from_rotation_x(FRAC_PI_2), dvec3(0.0, 3.0, 0.0)),
+            0.5, 2.0);
+        let floor = Collider::new_box(DMat4::IDENTITY, dvec3(10.0, 1.0, 10.0));
+
+        let hit = capsule.shape_cast(&floor, dvec3(0.0, -1.0, 0.0), 10.0).unwrap();
+        assert!((hit.toi - 1.0).abs() < 1e-5);
+        assert!(hit.normal.distance(dvec3(0.0, -1.0, 0.0)) < 1e-3);
+        assert!((hit.point1.y - 0.5).abs() < 1e-3);
+        assert!((hit.point2.y - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_shape_cast_unconverged_is_a_miss() {
+        // Grazing past a sphere converges slowly, running out of iterations must not report a hit.
+        let collider1 = Collider::new_sphere(DMat4::IDENTITY, 1.0);
+        let collider2 = Collider::new_sphere(DMat4::from_translation(dvec3(5.0, 2.0 + 1e-4, 0.0)), 1.0);
+
+        assert!(shape_cast(&collider1, &collider2, dvec3(1.0, 0.0, 0.0), 10.0, 1e-6, 3).is_none());
+        assert!(collider1.shape_cast(&collider2, dvec3(1.0, 0.0, 0.0), 10.0).is_none());
+    }
+}
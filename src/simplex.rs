@@ -0,0 +1,874 @@
+use glam::{DMat3, DVec3};
+
+use crate::gjk::SubDistance;
+
+/// A support point of the Minkowski difference `v = s0 - s1` together with the points on both shapes.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Vertex {
+    pub(crate) v: DVec3,
+    pub(crate) s0: DVec3,
+    pub(crate) s1: DVec3,
+    /// Direction `s1` was searched in, `s0` in the opposite one. Zero if unknown.
+    pub(crate) dir: DVec3,
+    /// Indices of `s0` and `s1` among the vertices of the shapes, see `SupportMap::core_support_vertex`.
+    pub(crate) index0: Option<usize>,
+    pub(crate) index1: Option<usize>,
+}
+
+/// Simplex of up to four vertices and its closest point `ray` to the origin.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Simplex {
+    pub(crate) vertices: [Vertex; 4],
+    pub(crate) len: usize,
+    pub(crate) ray: DVec3,
+    pub(crate) barycentric: [f64; 4],
+}
+
+impl Simplex {
+    pub(crate) fn new() -> Self {
+        Self {
+            vertices: [Vertex::default(); 4],
+            len: 0,
+            ray: DVec3::ZERO,
+            barycentric: [0.0; 4],
+        }
+    }
+
+    /// Resets the simplex to the single `vertex`.
+    pub(crate) fn set(&mut self, vertex: Vertex) {
+        self.vertices[0] = vertex;
+        self.len = 1;
+        self.barycentric[0] = 1.0;
+        self.ray = vertex.v;
+    }
+
+    pub(crate) fn push(&mut self, vertex: Vertex) {
+        self.vertices[self.len] = vertex;
+        self.barycentric[self.len] = 0.0;
+        self.len += 1;
+    }
+
+    pub(crate) fn pop(&mut self) {
+        self.len -= 1;
+    }
+
+    pub(crate) fn vertices(&self) -> &[Vertex] {
+        &self.vertices[..self.len]
+    }
+
+    /// Reduces the simplex to the sub simplex closest to the origin and updates `ray`.
+    /// Returns true if the simplex encloses the origin.
+    pub(crate) fn project_origen(&mut self) -> bool {
+        let previous_vertices = self.vertices;
+        let previous_len = self.len;
+
+        let inside = match self.len {
+            1 => {
+                self.ray = self.vertices[0].v;
+                self.barycentric[0] = 1.0;
+                false
+            }
+            2 => self.project_line_origen(),
+            3 => self.project_triangle_origen(),
+            4 => self.project_tetra_to_origen(),
+            _ => false,
+        };
+
+        if self.is_projection_valid(&previous_vertices[..previous_len], inside) {
+            return inside;
+        }
+
+        self.vertices = previous_vertices;
+        self.len = previous_len;
+        self.project_origen_exhaustive()
+    }
+
+    pub(crate) fn project(&mut self, sub_distance: SubDistance) -> bool {
+        match sub_distance {
+            SubDistance::Johnson => self.project_origen(),
+            SubDistance::SignedVolumes => self.project_origen_signed_volumes(),
+        }
+    }
+
+    /// Same as `project_origen` with the signed volumes sub distance algorithm of Montanari, Petrinic and Barbieri
+    /// ("Improving the GJK algorithm for faster and more reliable distance queries between convex objects", 2017).
+    /// It makes no assumption about which vertex was added last, so the result needs no validation.
+    pub(crate) fn project_origen_signed_volumes(&mut self) -> bool {
+        let sub_simplex = match self.len {
+            1 => SubSimplex::point(0, self.vertices[0].v),
+            2 => self.signed_volume_1d(0, 1),
+            3 => self.signed_volume_2d([0, 1, 2]),
+            4 => self.signed_volume_3d(),
+            _ => return false,
+        };
+
+        let vertices = self.vertices;
+        for i in 0..sub_simplex.len {
+            self.vertices[i] = vertices[sub_simplex.indices[i]];
+            self.barycentric[i] = sub_simplex.weights[i];
+        }
+        self.len = sub_simplex.len;
+        self.ray = sub_simplex.point;
+
+        sub_simplex.len == 4
+    }
+
+    fn signed_volume_3d(&self) -> SubSimplex {
+        let [a, b, c, d] = self.vertices.map(|vertex| vertex.v);
+        let volume = |p: DVec3, q: DVec3, r: DVec3, s: DVec3| (q - p).dot((r - p).cross(s - p));
+
+        // Volumes of the tetrahedra with one vertex replaced by the origin, they sum up to the full volume.
+        let det = volume(a, b, c, d);
+        let cofactors = [
+            volume(DVec3::ZERO, b, c, d),
+            volume(a, DVec3::ZERO, c, d),
+            volume(a, b, DVec3::ZERO, d),
+            volume(a, b, c, DVec3::ZERO),
+        ];
+
+        if det != 0.0 && cofactors.iter().all(|cofactor| cofactor * det >= 0.0) {
+            return SubSimplex {
+                indices: [0, 1, 2, 3],
+                weights: cofactors.map(|cofactor| cofactor / det),
+                len: 4,
+                point: DVec3::ZERO,
+            };
+        }
+
+        // The origin is beyond every face whose cofactor has the opposite sign of the volume.
+        let faces = [[1, 2, 3], [0, 2, 3], [0, 1, 3], [0, 1, 2]];
+        (0..4)
+            .filter(|j| det == 0.0 || cofactors[*j] * det < 0.0)
+            .map(|j| self.signed_volume_2d(faces[j]))
+            .min_by(|x, y| x.point.length_squared().total_cmp(&y.point.length_squared()))
+            .unwrap()
+    }
+
+    fn signed_volume_2d(&self, indices: [usize; 3]) -> SubSimplex {
+        let [a, b, c] = indices.map(|i| self.vertices[i].v);
+        let n = (b - a).cross(c - a);
+        let n_len2 = n.length_squared();
+
+        let edges = [[indices[1], indices[2]], [indices[0], indices[2]], [indices[0], indices[1]]];
+        if n_len2 == 0.0 {
+            return edges.iter()
+                .map(|[i, j]| self.signed_volume_1d(*i, *j))
+                .min_by(|x, y| x.point.length_squared().total_cmp(&y.point.length_squared()))
+                .unwrap();
+        }
+
+        // Projection of the origin onto the plane and the signed areas of the triangles with one vertex replaced by it.
+        let p0 = n * (a.dot(n) / n_len2);
+        let area = |p: DVec3, q: DVec3, r: DVec3| (q - p).cross(r - p).dot(n);
+        let cofactors = [area(p0, b, c), area(a, p0, c), area(a, b, p0)];
+
+        if cofactors.iter().all(|cofactor| *cofactor >= 0.0) {
+            return SubSimplex {
+                indices: [indices[0], indices[1], indices[2], 0],
+                weights: [cofactors[0] / n_len2, cofactors[1] / n_len2, cofactors[2] / n_len2, 0.0],
+                len: 3,
+                point: p0,
+            };
+        }
+
+        (0..3)
+            .filter(|k| cofactors[*k] < 0.0)
+            .map(|k| self.signed_volume_1d(edges[k][0], edges[k][1]))
+            .min_by(|x, y| x.point.length_squared().total_cmp(&y.point.length_squared()))
+            .unwrap()
+    }
+
+    fn signed_volume_1d(&self, i: usize, j: usize) -> SubSimplex {
+        let a = self.vertices[i].v;
+        let b = self.vertices[j].v;
+        let t = b - a;
+        let t_len2 = t.length_squared();
+
+        let (weight_a, weight_b) = if t_len2 == 0.0 {
+            (1.0, 0.0)
+        } else {
+            (b.dot(t) / t_len2, -a.dot(t) / t_len2)
+        };
+
+        if weight_a < 0.0 {
+            SubSimplex::point(j, b)
+        } else if weight_b <= 0.0 {
+            SubSimplex::point(i, a)
+        } else {
+            SubSimplex {
+                indices: [i, j, 0, 0],
+                weights: [weight_a, weight_b, 0.0, 0.0],
+                len: 2,
+                point: weight_a * a + weight_b * b,
+            }
+        }
+    }
+
+    /// Points on both shapes corresponding to `ray`.
+    pub(crate) fn closest_points(&self) -> (DVec3, DVec3) {
+        let mut point1 = DVec3::ZERO;
+        let mut point2 = DVec3::ZERO;
+        for i in 0..self.len {
+            point1 += self.barycentric[i] * self.vertices[i].s0;
+            point2 += self.barycentric[i] * self.vertices[i].s1;
+        }
+        (point1, point2)
+    }
+
+    /// The branchy projections assume that the newest vertex is part of the closest feature,
+    /// which does not hold for the accelerated support directions.
+    fn is_projection_valid(&self, previous_simplex: &[Vertex], inside: bool) -> bool {
+        let scale = previous_simplex.iter()
+            .fold(0.0_f64, |scale, vertex| scale.max(vertex.v.length_squared()));
+        let eps = 1e-10 * scale;
+
+        if self.barycentric[..self.len].iter().any(|weight| *weight < -1e-10) {
+            return false;
+        }
+
+        // The ray is the closest point to the origin iff no vertex lies beyond it.
+        let ray_len2 = self.ray.length_squared();
+        inside || previous_simplex.iter().all(|vertex| vertex.v.dot(self.ray) >= ray_len2 - eps)
+    }
+
+    /// Finds the closest sub simplex to the origin by checking all of them.
+    fn project_origen_exhaustive(&mut self) -> bool {
+        let n = self.len;
+        let mut best_mask = 0;
+        let mut best_len2 = f64::INFINITY;
+
+        for mask in 1..(1_usize << n) {
+            let (indices, len) = mask_indices(mask, n);
+            let Some(weights) = self.affine_barycentric(&indices[..len]) else {
+                continue;
+            };
+            if weights[..len].iter().any(|weight| *weight < 0.0) {
+                continue;
+            }
+
+            if len == 4 {
+                self.ray = DVec3::ZERO;
+                self.set_tetra_barycentric();
+                return true;
+            }
+
+            let point: DVec3 = (0..len)
+                .map(|k| self.vertices[indices[k]].v * weights[k])
+                .sum();
+            if point.length_squared() < best_len2 {
+                best_len2 = point.length_squared();
+                best_mask = mask;
+            }
+        }
+
+        let (indices, len) = mask_indices(best_mask, n);
+        match indices[..len] {
+            [a_index] => {
+                self.origen_to_point(a_index, self.vertices[a_index].v);
+                false
+            }
+            [b_index, a_index] => {
+                let a = self.vertices[a_index].v;
+                let b = self.vertices[b_index].v;
+                self.origen_to_segment(a_index, b_index, a, b, b - a, (b - a).dot(-a));
+                false
+            }
+            [c_index, b_index, a_index] => {
+                let a = self.vertices[a_index].v;
+                let abc = (self.vertices[b_index].v - a).cross(self.vertices[c_index].v - a);
+                self.origen_to_triangle(a_index, b_index, c_index, abc, abc.dot(-a))
+            }
+            _ => {
+                self.origen_to_point(n - 1, self.vertices[n - 1].v);
+                false
+            }
+        }
+    }
+
+    /// Barycentric coordinates of the projection of the origin onto the affine hull of the vertices,
+    /// the first `indices.len()` weights are set.
+    fn affine_barycentric(&self, indices: &[usize]) -> Option<[f64; 4]> {
+        let p0 = self.vertices[indices[0]].v;
+        let mut edges = [DVec3::ZERO; 3];
+        for (edge, i) in edges.iter_mut().zip(indices[1..].iter()) {
+            *edge = self.vertices[*i].v - p0;
+        }
+
+        let mut t = [0.0; 3];
+        match indices.len() - 1 {
+            0 => {}
+            1 => {
+                let det = edges[0].length_squared();
+                if det <= 0.0 {
+                    return None;
+                }
+                t[0] = -p0.dot(edges[0]) / det;
+            }
+            2 => {
+                let [e0, e1, _] = edges;
+                let (g00, g01, g11) = (e0.dot(e0), e0.dot(e1), e1.dot(e1));
+                let (r0, r1) = (-p0.dot(e0), -p0.dot(e1));
+                let det = g00 * g11 - g01 * g01;
+                if det <= 1e-14 * g00 * g11 {
+                    return None;
+                }
+                t[0] = (r0 * g11 - r1 * g01) / det;
+                t[1] = (g00 * r1 - g01 * r0) / det;
+            }
+            _ => {
+                let m = DMat3::from_cols(edges[0], edges[1], edges[2]);
+                let det = m.determinant();
+                if det.abs() <= 1e-14 * edges[0].length() * edges[1].length() * edges[2].length() {
+                    return None;
+                }
+                t = (m.inverse() * -p0).to_array();
+            }
+        }
+
+        Some([1.0 - t[0] - t[1] - t[2], t[0], t[1], t[2]])
+    }
+
+    fn origen_to_point(&mut self, a_index: usize, a: DVec3) {
+        self.ray = a;
+        self.vertices[0] = self.vertices[a_index];
+        self.len = 1;
+        self.barycentric[0] = 1.0;
+    }
+    
+    fn origen_to_segment(
+        &mut self,
+        a_index: usize,
+        b_index: usize,
+        a: DVec3,
+        b: DVec3,
+        ab: DVec3,
+        ab_dot_a0: f64,
+    ) {
+        let ab_len2 = ab.length_squared();
+        self.ray = (ab.dot(b) * a + ab_dot_a0 * b) / ab_len2;
+        let (a_vertex, b_vertex) = (self.vertices[a_index], self.vertices[b_index]);
+        self.vertices[0] = b_vertex;
+        self.vertices[1] = a_vertex;
+        self.len = 2;
+        self.barycentric[0] = ab_dot_a0 / ab_len2;
+        self.barycentric[1] = ab.dot(b) / ab_len2;
+    }
+    
+    fn origen_to_triangle(
+        &mut self,
+        a_index: usize,
+        b_index: usize,
+        c_index: usize,
+        abc: DVec3,
+        abc_dot_a0: f64,
+    ) -> bool {
+        // Read the vertices first, the indices may overlap with the slots written below.
+        let a = self.vertices[a_index];
+        let b = self.vertices[b_index];
+        let c = self.vertices[c_index];
+
+        if abc_dot_a0 == 0.0 {
+            self.vertices[0] = c;
+            self.vertices[1] = b;
+            self.vertices[2] = a;
+            self.len = 3;
+    
+            self.ray = DVec3::ZERO;
+            self.set_triangle_barycentric();
+            return true;
+        }
+    
+        if abc_dot_a0 > 0.0 {
+            self.vertices[0] = c;
+            self.vertices[1] = b;
+        } else {
+            self.vertices[0] = b;
+            self.vertices[1] = c;
+        }
+    
+        self.vertices[2] = a;
+        self.len = 3;
+    
+        self.ray = -abc_dot_a0 / abc.length_squared() * abc;
+        if abc == DVec3::ZERO {
+            self.ray = abc;
+        }
+        self.set_triangle_barycentric();
+    
+        false
+    }
+
+    fn set_triangle_barycentric(&mut self) {
+        let a = self.vertices[0].v;
+        let b = self.vertices[1].v;
+        let c = self.vertices[2].v;
+        let p = self.ray;
+
+        let n = (b - a).cross(c - a);
+        let n_len2 = n.length_squared();
+        if n_len2 == 0.0 {
+            self.barycentric[..3].copy_from_slice(&[1.0, 0.0, 0.0]);
+            return;
+        }
+
+        let u = (b - p).cross(c - p).dot(n) / n_len2;
+        let v = (c - p).cross(a - p).dot(n) / n_len2;
+        self.barycentric[..3].copy_from_slice(&[u, v, 1.0 - u - v]);
+    }
+
+    fn set_tetra_barycentric(&mut self) {
+        let a = self.vertices[0].v;
+        let b = self.vertices[1].v;
+        let c = self.vertices[2].v;
+        let d = self.vertices[3].v;
+
+        let volume = (b - a).dot((c - a).cross(d - a));
+        if volume == 0.0 {
+            self.barycentric = [1.0, 0.0, 0.0, 0.0];
+            return;
+        }
+
+        let wa = b.dot(c.cross(d)) / volume;
+        let wb = -a.dot(c.cross(d)) / volume;
+        let wc = a.dot(b.cross(d)) / volume;
+        self.barycentric = [wa, wb, wc, 1.0 - wa - wb - wc];
+    }
+    
+    fn project_line_origen(&mut self) -> bool {
+        let a_index = 1;
+        let b_index = 0;
+    
+        let a = self.vertices[a_index].v;
+        let b = self.vertices[b_index].v;
+    
+        let ab = b - a;
+        let d = ab.dot(-a);
+    
+        if d == 0.0 {
+            /* Two extremely unlikely cases:
+                - AB is orthogonal to A: should never happen because it means the support
+                function did not do any progress and GJK should have stopped.
+                - A == origin
+            In any case, A is the closest to the origin */
+            self.origen_to_point(a_index, a);
+            return a == DVec3::ZERO;
+        }
+    
+        if d < 0.0 {
+            self.origen_to_point(a_index, a);
+        } else {
+            self.origen_to_segment(a_index, b_index, a, b, ab, d);
+        }
+    
+        false
+    }
+    
+    fn project_triangle_origen(&mut self) -> bool {
+        let a_index = 2;
+        let b_index = 1;
+        let c_index = 0;
+    
+        let a = self.vertices[a_index].v;
+        let b = self.vertices[b_index].v;
+        let c = self.vertices[c_index].v;
+    
+        let ab = b - a;
+        let ac = c - a;
+        let abc = ab.cross(ac);
+    
+        let edge_ac2o = abc.cross(ac).dot(-a);
+    
+        let t_b = |data: &mut Self| {
+            let towards_b = ab.dot(-a);
+            if towards_b < 0.0 {
+                data.origen_to_point(a_index, a);
+            } else {
+                data.origen_to_segment(a_index, b_index, a, b, ab, towards_b)
+            }
+        };
+    
+        if edge_ac2o >= 0.0 {
+            let towards_c = ac.dot(-a);
+            if towards_c >= 0.0 {
+                self.origen_to_segment(a_index, c_index, a, c, ac, towards_c)
+            } else {
+                t_b(self);
+            }
+        } else {
+            let edge_ab2o = ab.cross(abc).dot(-a);
+            if edge_ab2o >= 0.0 {
+                t_b(self);
+            } else {
+                return self.origen_to_triangle(a_index, b_index, c_index, abc, abc.dot(-a));
+            }
+        }
+    
+        false
+    }
+    
+    fn project_tetra_to_origen(&mut self) -> bool {
+        let a_index = 3;
+        let b_index = 2;
+        let c_index = 1;
+        let d_index = 0;
+    
+        let a = self.vertices[a_index].v;
+        let b = self.vertices[b_index].v;
+        let c = self.vertices[c_index].v;
+        let d = self.vertices[d_index].v;
+    
+        let aa = a.length_squared();
+    
+        let da = d.dot(a);
+        let db = d.dot(b);
+        let dc = d.dot(c);
+        let dd = d.dot(d);
+        let da_aa = da - aa;
+    
+        let ca = c.dot(a);
+        let cb = c.dot(b);
+        let cc = c.dot(c);
+        let cd = dc;
+        let ca_aa = ca - aa;
+    
+        let ba = b.dot(a);
+        let bb = b.dot(b);
+        let bc = cb;
+        let bd = db;
+        let ba_aa = ba - aa;
+        let ba_ca = ba - ca;
+        let ca_da = ca - da;
+        let da_ba = da - ba;
+    
+        let a_cross_b = a.cross(b);
+        let a_cross_c = a.cross(c);
+    
+        let region_inside = |data: &mut Self| {
+            data.ray = DVec3::ZERO;
+            data.set_tetra_barycentric();
+            true
+        };
+    
+        let region_abc = |data: &mut Self| {
+            data.origen_to_triangle(
+                a_index,
+                b_index,
+                c_index,
+                (b - a).cross(c - a),
+                -c.dot(a_cross_b),
+            )
+        };
+    
+        let region_acd = |data: &mut Self| {
+            data.origen_to_triangle(
+                a_index,
+                c_index,
+                d_index,
+                (c - a).cross(d - a),
+                -d.dot(a_cross_c),
+            )
+        };
+    
+        let region_adb = |data: &mut Self| {
+            data.origen_to_triangle(
+                a_index,
+                d_index,
+                b_index,
+                (d - a).cross(b - a),
+                d.dot(a_cross_b),
+            )
+        };
+    
+        let region_ab = |data: &mut Self| {
+            data.origen_to_segment(a_index, b_index, a, b, b - a, -ba_aa)
+        };
+    
+        let region_ac = |data: &mut Self| {
+            data.origen_to_segment(a_index, c_index, a, c, c - a, -ca_aa)
+        };
+    
+        let region_ad = |data: &mut Self| {
+            data.origen_to_segment(a_index, d_index, a, d, d - a, -da_aa)
+        };
+    
+        let region_a = |data: &mut Self| data.origen_to_point(a_index, a);
+    
+        if ba_aa <= 0.0 {
+            if -d.dot(a_cross_b) <= 0.0 {
+                if ba * da_ba + bd * ba_aa - bb * da_aa <= 0.0 {
+                    if da_aa <= 0.0 {
+                        if ba * ba_ca + bb * ca_aa - bc * ba_aa <= 0.0 {
+                            region_abc(self);
+                        } else {
+                            region_ab(self);
+                        }
+                    } else {
+                        if ba * ba_ca + bb * ca_aa - bc * ba_aa <= 0.0 {
+                            if ca * ba_ca + cb * ca_aa - cc * ba_aa <= 0.0 {
+                                if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
+                                    region_acd(self);
+                                } else {
+                                    region_ac(self);
+                                }
+                            } else {
+                                region_abc(self);
+                            }
+                        } else {
+                            region_ab(self);
+                        }
+                    }
+                } else {
+                    if da * da_ba + dd * ba_aa - db * da_aa <= 0.0 {
+                        region_adb(self);
+                    } else {
+                        if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
+                            if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
+                                region_ad(self);
+                            } else {
+                                region_acd(self);
+                            }
+                        } else {
+                            if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
+                                region_ad(self);
+                            } else {
+                                region_ac(self);
+                            }
+                        }
+                    }
+                }
+            } else {
+                if c.dot(a_cross_b) <= 0.0 {
+                    if ba * ba_ca + bb * ca_aa - bc * ba_aa <= 0.0 {
+                        if ca * ba_ca + cb * ca_aa - cc * ba_aa <= 0.0 {
+                            if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
+                                region_acd(self);
+                            } else {
+                                region_ac(self);
+                            }
+                        } else {
+                            region_abc(self);
+                        }
+                    } else {
+                        region_ad(self);
+                    }
+                } else {
+                    if d.dot(a_cross_c) <= 0.0 {
+                        if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
+                            if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
+                                region_ad(self);
+                            } else {
+                                region_acd(self);
+                            }
+                        } else {
+                            if ca_aa <= 0.0 {
+                                region_ac(self);
+                            } else {
+                                region_ad(self);
+                            }
+                        }
+                    } else {
+                        return region_inside(self);
+                    }
+                }
+            }
+        } else {
+            if ca_aa <= 0.0 {
+                if d.dot(a_cross_c) <= 0.0 {
+                    if da_aa <= 0.0 {
+                        if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
+                            if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
+                                if da * da_ba + dd * ba_aa - db * da_aa <= 0.0 {
+                                    region_adb(self);
+                                } else {
+                                    region_ad(self);
+                                }
+                            } else {
+                                region_acd(self);
+                            }
+                        } else {
+                            if ca * ba_ca + cb * ca_aa - cc * ba_aa <= 0.0 {
+                                region_ac(self);
+                            } else {
+                                region_abc(self);
+                            }
+                        }
+                    } else {
+                        if ca * ba_ca + cb * ca_aa - cc * ba_aa <= 0.0 {
+                            if ca * ca_da + cc * da_aa - cd * ca_aa <= 0.0 {
+                                region_acd(self);
+                            } else {
+                                region_ac(self);
+                            }
+                        } else {
+                            if c.dot(a_cross_b) <= 0.0 {
+                                region_abc(self);
+                            } else {
+                                region_acd(self);
+                            }
+                        }
+                    }
+                } else {
+                    if c.dot(a_cross_b) <= 0.0 {
+                        if ca * ba_ca + cb * ca_aa - cc * ba_aa <= 0.0 {
+                            region_ac(self);
+                        } else {
+                            region_abc(self);
+                        }
+                    } else {
+                        if -d.dot(a_cross_b) <= 0.0 {
+                            if da * da_ba + dd * ba_aa - db * da_aa <= 0.0 {
+                                region_adb(self);
+                            } else {
+                                region_ad(self);
+                            }
+                        } else {
+                            return region_inside(self);
+                        }
+                    }
+                }
+            } else {
+                if da_aa <= 0.0 {
+                    if -d.dot(a_cross_b) <= 0.0 {
+                        if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
+                            if da * da_ba + dd * ba_aa - db * da_aa <= 0.0 {
+                                region_adb(self);
+                            } else {
+                                region_ad(self);
+                            }
+                        } else {
+                            if d.dot(a_cross_c) <= 0.0 {
+                                region_acd(self);
+                            } else {
+                                region_adb(self);
+                            }
+                        }
+                    } else {
+                        if d.dot(a_cross_c) <= 0.0 {
+                            if da * ca_da + dc * da_aa - dd * ca_aa <= 0.0 {
+                                region_ad(self);
+                            } else {
+                                region_acd(self);
+                            }
+                        } else {
+                            return region_inside(self);
+                        }
+                    }
+                } else {
+                    region_a(self);
+                }
+            }
+        }
+    
+        false
+    }
+}
+
+/// Indices of the vertices selected by the bits of `mask` and their count.
+fn mask_indices(mask: usize, n: usize) -> ([usize; 4], usize) {
+    let mut indices = [0; 4];
+    let mut len = 0;
+    for i in 0..n {
+        if mask & (1 << i) != 0 {
+            indices[len] = i;
+            len += 1;
+        }
+    }
+    (indices, len)
+}
+
+/// Closest sub simplex to the origin found by the signed volumes algorithm.
+#[derive(Copy, Clone, Debug)]
+struct SubSimplex {
+    indices: [usize; 4],
+    weights: [f64; 4],
+    len: usize,
+    point: DVec3,
+}
+
+impl SubSimplex {
+    fn point(index: usize, point: DVec3) -> Self {
+        Self {
+            indices: [index, 0, 0, 0],
+            weights: [1.0, 0.0, 0.0, 0.0],
+            len: 1,
+            point,
+        }
+    }
+}
+
+impl Vertex {
+    pub(crate) fn new(s0: DVec3, s1: DVec3) -> Self {
+        Self { v: s0 - s1, s0, s1, dir: DVec3::ZERO, index0: None, index1: None }
+    }
+}
+
+impl Default for Vertex {
+    fn default() -> Self {
+        Self::new(DVec3::ZERO, DVec3::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::{dvec3, DVec3};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::{Simplex, Vertex};
+
+    #[test]
+    fn test_signed_volumes_matches_exhaustive() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for len in 1..=4 {
+            for _ in 0..2000 {
+                let mut simplex = Simplex::new();
+                for _ in 0..len {
+                    let v = dvec3(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+                    simplex.push(Vertex::new(v, dvec3(0.0, 0.0, 0.0)));
+                }
+
+                let mut signed_volumes = simplex;
+                let mut exhaustive = simplex;
+                let inside = signed_volumes.project_origen_signed_volumes();
+                assert!(inside == exhaustive.project_origen_exhaustive());
+                assert!(signed_volumes.ray.distance(exhaustive.ray) < 1e-9);
+
+                let point: DVec3 = (0..signed_volumes.len)
+                    .map(|i| signed_volumes.barycentric[i] * signed_volumes.vertices[i].v)
+                    .sum();
+                assert!(point.distance(signed_volumes.ray) < 1e-9);
+                assert!(signed_volumes.barycentric[..signed_volumes.len].iter().all(|weight| *weight >= 0.0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_johnson_falls_back_when_newest_vertex_is_not_closest() {
+        // The branchy projection assumes the origin lies in the region of the newest vertex `a`,
+        // here it lies beyond the older vertex `b` and the segment weights would be negative.
+        let mut simplex = Simplex::new();
+        simplex.push(Vertex::new(dvec3(1.0, 1.0, 0.0), DVec3::ZERO));
+        simplex.push(Vertex::new(dvec3(3.0, 1.0, 0.0), DVec3::ZERO));
+        assert!(!simplex.project_origen());
+        assert!(simplex.len == 1);
+        assert!(simplex.ray == dvec3(1.0, 1.0, 0.0));
+
+        // Random simplices have no preferred vertex, the validated projection has to match the exhaustive one.
+        let mut rng = StdRng::seed_from_u64(1);
+        for len in 2..=4 {
+            for _ in 0..2000 {
+                let mut simplex = Simplex::new();
+                for _ in 0..len {
+                    let v = dvec3(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+                    simplex.push(Vertex::new(v, DVec3::ZERO));
+                }
+
+                let mut johnson = simplex;
+                let mut exhaustive = simplex;
+                assert!(johnson.project_origen() == exhaustive.project_origen_exhaustive());
+                assert!(johnson.ray.distance(exhaustive.ray) < 1e-9);
+                assert!(johnson.barycentric[..johnson.len].iter().all(|weight| *weight >= -1e-10));
+            }
+        }
+    }
+}
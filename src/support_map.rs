@@ -0,0 +1,72 @@
+use crate::scalar::Scalar;
+
+/// A convex shape described by its support function, usable as input of the GJK queries.
+///
+/// Shapes implement it for `f64` unless they are generic over the [`Scalar`], see `Collider`.
+pub trait SupportMap<F: Scalar = f64> {
+    /// Furthest point of the shape along `dir` in world space.
+    fn support_point(&self, dir: F::Vec3) -> F::Vec3;
+
+    /// Radius of the sphere swept around the core shape,
+    /// `support_point(dir)` equals `core_support_point(dir) + dir.normalize() * margin()`.
+    fn margin(&self) -> F {
+        F::ZERO
+    }
+
+    /// Furthest point of the shape without its margin along `dir` in world space.
+    fn core_support_point(&self, dir: F::Vec3) -> F::Vec3 {
+        self.support_point(dir)
+    }
+
+    /// Like `core_support_point`, together with the index of the returned vertex for shapes with finitely
+    /// many vertices like convex hulls. `GjkCache` keeps the index to find the same vertex after the shape moved.
+    fn core_support_vertex(&self, dir: F::Vec3) -> (F::Vec3, Option<usize>) {
+        (self.core_support_point(dir), None)
+    }
+
+    /// Vertex `index` of the core in world space, see `core_support_vertex`. `None` if the index is out of range.
+    fn core_vertex(&self, _index: usize) -> Option<F::Vec3> {
+        None
+    }
+
+    /// Center of the shape in world space, if known.
+    fn center(&self) -> Option<F::Vec3> {
+        None
+    }
+
+    /// Radius of a sphere around `center` that encloses the shape, if known.
+    fn bounding_radius(&self) -> Option<F> {
+        None
+    }
+}
+
+impl<F: Scalar, T: SupportMap<F> + ?Sized> SupportMap<F> for &T {
+    fn support_point(&self, dir: F::Vec3) -> F::Vec3 {
+        (**self).support_point(dir)
+    }
+
+    fn margin(&self) -> F {
+        (**self).margin()
+    }
+
+    fn core_support_point(&self, dir: F::Vec3) -> F::Vec3 {
+        (**self).core_support_point(dir)
+    }
+
+    fn core_support_vertex(&self, dir: F::Vec3) -> (F::Vec3, Option<usize>) {
+        (**self).core_support_vertex(dir)
+    }
+
+    fn core_vertex(&self, index: usize) -> Option<F::Vec3> {
+        (**self).core_vertex(index)
+    }
+
+    fn center(&self) -> Option<F::Vec3> {
+        (**self).center()
+    }
+
+    fn bounding_radius(&self) -> Option<F> {
+        (**self).bounding_radius()
+    }
+}
+
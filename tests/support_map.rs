@@ -0,0 +1,26 @@
+use glam::{dvec3, DMat4, DVec3};
+use gjk::{colliders::Collider, gjk::{GJKNesterov, GjkStatus}, support_map::SupportMap};
+
+struct Segment {
+    a: DVec3,
+    b: DVec3,
+}
+
+impl SupportMap for Segment {
+    fn support_point(&self, dir: DVec3) -> DVec3 {
+        if self.a.dot(dir) > self.b.dot(dir) { self.a } else { self.b }
+    }
+}
+
+#[test]
+fn test_custom_support_map() {
+    let segment = Segment { a: dvec3(-1.0, 3.0, 0.0), b: dvec3(1.0, 3.0, 0.0) };
+    let sphere = Collider::new_sphere(DMat4::IDENTITY, 1.0);
+
+    let mut gjk = GJKNesterov::new(None, 1e-6);
+    let result = gjk.distance_nesterov_accelerated(&segment, &sphere, 100);
+
+    assert!(result.status == GjkStatus::Separated);
+    assert!((result.distance - 2.0).abs() < 1e-3);
+    assert!(gjk.closest_points().point1.distance(dvec3(0.0, 3.0, 0.0)) < 1e-2);
+}
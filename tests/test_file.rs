@@ -1,25 +1,83 @@
-use gjk::{json_loder::load_test_file, gjk::GJKNesterov};
+use gjk::{colliders::Collider, json_loder::load_test_file, gjk::{GJKNesterov, GjkCache, GjkStatus}};
+use glam::{DMat4, DQuat, dvec3};
+use rand::{Rng, SeedableRng, rngs::StdRng};
 
 #[test]
 fn test_run_test_file() {
 
-    let path = "../data/test_data.json";
-    let test_data = load_test_file(path);
+    // Regenerate with `cargo run --release --features serde --bin gen_test_data`.
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/data/test_data.json");
+    let test_data = load_test_file(path).unwrap();
 
     let mut iteration_sum = 0;
+    let mut gjk = GJKNesterov::new(None, 1e-6);
 
     for (i, data) in test_data.iter().enumerate() {
         println!("Case: {i}");
 
-        let mut gjk = GJKNesterov::new(None, 1e-6);
+        let result = gjk.distance_nesterov_accelerated(&data.0, &data.1, 100);
 
-        let (_, test_distance, iterations) = gjk.distance_nesterov_accelerated(&data.0, &data.1, 100);
+        assert!(result.status != GjkStatus::MaxIterationsReached);
+        assert!((result.distance.max(0.0) - data.2).abs() < 1e-4);
 
-        assert!((test_distance - data.2).abs() < 0.01);
-
-        println!("Interations: {iterations}"); 
-        iteration_sum += iterations;
+        println!("Interations: {}", result.iterations); 
+        iteration_sum += result.iterations;
     }
 
     println!("Interations per Case: {:?}", (iteration_sum as f32) / test_data.len() as f32); 
 }
+
+#[test]
+fn test_warm_start_coherent_sequences() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let mut cold_iteration_sum = 0;
+    let mut ray_iteration_sum = 0;
+    let mut warm_iteration_sum = 0;
+    let mut frames = 0;
+
+    let mut cold_gjk = GJKNesterov::new(None, 1e-6);
+    let mut ray_gjk = GJKNesterov::new(None, 1e-6);
+    let mut warm_gjk = GJKNesterov::new(None, 1e-6);
+
+    for _ in 0..200 {
+        let collider1 = Collider::new_random_any(&mut rng, 0.5..3.0);
+        let collider2 = Collider::new_random_any(&mut rng, 0.5..3.0);
+
+        // collider2 drifts and spins slowly, every frame is a small step of the previous one.
+        let step = dvec3(rng.gen_range(-0.05..0.05), rng.gen_range(-0.05..0.05), rng.gen_range(-0.05..0.05));
+        let spin = DQuat::from_rotation_y(rng.gen_range(-0.02..0.02));
+        let mut pose = DMat4::from_translation(collider2.center) * DMat4::from_mat3(collider2.transform);
+
+        let mut cache = GjkCache::default();
+        for _ in 0..50 {
+            pose = DMat4::from_translation(step) * pose * DMat4::from_quat(spin);
+            let moved = collider2.with_transform(pose);
+
+            let cold = cold_gjk.distance_nesterov_accelerated(&collider1, &moved, 100);
+
+            // Only the direction of the last frame, without its simplex.
+            ray_gjk.warm_start(&GjkCache { ray: cache.ray, ..Default::default() });
+            let ray = ray_gjk.distance_nesterov_accelerated(&collider1, &moved, 100);
+
+            warm_gjk.warm_start(&cache);
+            let warm = warm_gjk.distance_nesterov_accelerated(&collider1, &moved, 100);
+            cache = warm_gjk.cache();
+
+            assert!(warm.status != GjkStatus::MaxIterationsReached);
+            assert!((warm.distance - cold.distance).abs() < 1e-3);
+
+            cold_iteration_sum += cold.iterations;
+            ray_iteration_sum += ray.iterations;
+            warm_iteration_sum += warm.iterations;
+            frames += 1;
+        }
+    }
+
+    println!("Interations per Frame: cold {:?}, ray {:?}, warm {:?}",
+        (cold_iteration_sum as f32) / frames as f32,
+        (ray_iteration_sum as f32) / frames as f32,
+        (warm_iteration_sum as f32) / frames as f32);
+    assert!(warm_iteration_sum < cold_iteration_sum / 2);
+    assert!(warm_iteration_sum < ray_iteration_sum * 4 / 5);
+}